@@ -8,6 +8,8 @@ use multihash_derive::{Hasher, MultihashDigest};
 #[derive(Default, Debug)]
 pub struct Sha2_256Truncated20(Sha2_256);
 impl Hasher for Sha2_256Truncated20 {
+    const OUTPUT_SIZE: Option<usize> = Some(20);
+
     fn update(&mut self, input: &[u8]) {
         self.0.update(input)
     }
@@ -25,7 +27,8 @@ pub enum Code {
     /// Example for using a custom hasher which returns truncated hashes
     #[mh(code = 0x12, hasher = Sha2_256Truncated20)]
     Sha2_256Truncated20,
-    /// Example for using a hasher with a bit size that is not exported by default
+    /// Example for using a hasher with a bit size that is not exported by default. For BLAKE2
+    /// on its own, `multihash_codetable::Blake2bCode` covers all sizes without a custom table.
     #[mh(code = 0xb219, hasher = multihash_codetable::Blake2bHasher::<25>)]
     Blake2b200,
 }
@@ -0,0 +1,104 @@
+//! Codes for all output lengths of BLAKE2b and BLAKE2s.
+//!
+//! Multicodec reserves a code for every output length in bytes, `blake2b-8` to `blake2b-512` are
+//! `0xb201` to `0xb240` and `blake2s-8` to `blake2s-256` are `0xb241` to `0xb260`.
+
+use core::fmt;
+
+use multihash_derive::{Error, Multihash, MultihashDigest, UnsupportedCode};
+
+macro_rules! derive_blake2_code {
+    ($module:ident, $name:ident, $alg:literal, $first_code:literal, $max_len:literal) => {
+        #[doc = concat!("A ", $alg, " code for an output length of 1 to ", $max_len, " bytes.")]
+        ///
+        #[doc = concat!(
+            "The code for a length of `len` bytes is `", stringify!($first_code), " + len - 1`."
+        )]
+        #[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub struct $name {
+            len: u8,
+        }
+
+        impl $name {
+            /// The maximum output length in bytes.
+            pub const MAX_LEN: usize = $max_len;
+
+            /// Returns the code for the given output length in bytes.
+            ///
+            /// Returns `None` if the length is zero or larger than [`Self::MAX_LEN`].
+            pub const fn new(len: usize) -> Option<Self> {
+                if len == 0 || len > Self::MAX_LEN {
+                    return None;
+                }
+                Some(Self { len: len as u8 })
+            }
+
+            /// Returns the code for the given multicodec code.
+            ///
+            /// Returns `None` if the code is not in the range of this hash function.
+            pub const fn from_code(code: u64) -> Option<Self> {
+                if code < $first_code || code >= $first_code + $max_len {
+                    return None;
+                }
+                Some(Self {
+                    len: (code - $first_code + 1) as u8,
+                })
+            }
+
+            /// Returns the multicodec code.
+            pub const fn code(&self) -> u64 {
+                $first_code + self.len as u64 - 1
+            }
+
+            /// Returns the output length in bytes.
+            #[allow(clippy::len_without_is_empty)]
+            pub const fn len(&self) -> usize {
+                self.len as usize
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}-{}", $alg, self.len() * 8)
+            }
+        }
+
+        impl TryFrom<u64> for $name {
+            type Error = UnsupportedCode;
+
+            fn try_from(code: u64) -> Result<Self, Self::Error> {
+                Self::from_code(code).ok_or(UnsupportedCode(code))
+            }
+        }
+
+        impl From<$name> for u64 {
+            fn from(code: $name) -> Self {
+                code.code()
+            }
+        }
+
+        impl MultihashDigest<64> for $name {
+            fn digest(&self, input: &[u8]) -> Multihash<64> {
+                let hash = ::$module::Params::new()
+                    .hash_length(self.len())
+                    .hash(input);
+                self.wrap(hash.as_bytes())
+                    .expect("the digest has the length of the code")
+            }
+
+            fn wrap(&self, digest: &[u8]) -> Result<Multihash<64>, Error> {
+                Multihash::wrap(self.code(), digest)
+            }
+
+            fn digest_size(&self) -> Option<usize> {
+                Some(self.len())
+            }
+        }
+    };
+}
+
+#[cfg(feature = "blake2b")]
+derive_blake2_code!(blake2b_simd, Blake2bCode, "BLAKE2b", 0xb201, 64);
+
+#[cfg(feature = "blake2s")]
+derive_blake2_code!(blake2s_simd, Blake2sCode, "BLAKE2s", 0xb241, 32);
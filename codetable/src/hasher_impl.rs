@@ -2,7 +2,8 @@
     feature = "strobe",
     feature = "blake2b",
     feature = "blake2s",
-    feature = "blake3"
+    feature = "blake3",
+    feature = "sha3"
 ))]
 macro_rules! derive_write {
     ($name:ident) => {
@@ -24,26 +25,63 @@ macro_rules! derive_write {
 
 #[cfg(any(feature = "blake2b", feature = "blake2s"))]
 macro_rules! derive_hasher_blake {
-    ($module:ident, $name:ident) => {
+    ($module:ident, $name:ident, $code:ty) => {
         /// Multihash hasher.
         #[derive(Debug)]
         pub struct $name<const S: usize> {
+            params: $module::Params,
             state: $module::State,
             digest: [u8; S],
         }
 
-        impl<const S: usize> Default for $name<S> {
-            fn default() -> Self {
+        impl<const S: usize> $name<S> {
+            /// Creates a keyed hasher, e.g. to compute a MAC.
+            ///
+            /// Panics if the key is longer than supported by the hash function.
+            pub fn with_key(key: &[u8]) -> Self {
+                Self::with_params(key, &[], &[])
+            }
+
+            /// Creates a hasher with a key, a salt and a personalization.
+            ///
+            /// Empty values are not used, shorter salts and personalizations are padded with
+            /// zeros. Panics if any of them is longer than supported by the hash function.
+            pub fn with_params(key: &[u8], salt: &[u8], personal: &[u8]) -> Self {
                 let mut params = $module::Params::new();
-                params.hash_length(S);
+                params.hash_length(S).key(key).salt(salt).personal(personal);
                 Self {
                     state: params.to_state(),
+                    params,
                     digest: [0; S],
                 }
             }
+
+            /// Returns the multihash code for the output length of the hasher.
+            ///
+            /// Keyed, salted and personalized digests use the same code as plain ones.
+            pub fn code(&self) -> $code {
+                <$code>::new(S).expect("the hasher only supports valid output lengths")
+            }
+
+            /// Finalizes the hash and wraps the digest in a multihash with the matching code.
+            pub fn finalize_multihash(&mut self) -> multihash_derive::Multihash<64> {
+                use multihash_derive::{Hasher as _, MultihashDigest as _};
+
+                let code = self.code();
+                code.wrap(self.finalize())
+                    .expect("the digest has the length of the code")
+            }
+        }
+
+        impl<const S: usize> Default for $name<S> {
+            fn default() -> Self {
+                Self::with_params(&[], &[], &[])
+            }
         }
 
         impl<const S: usize> multihash_derive::Hasher for $name<S> {
+            const OUTPUT_SIZE: Option<usize> = Some(S);
+
             fn update(&mut self, input: &[u8]) {
                 self.state.update(input);
             }
@@ -57,8 +95,8 @@ macro_rules! derive_hasher_blake {
             }
 
             fn reset(&mut self) {
-                let Self { state, .. } = Self::default();
-                self.state = state;
+                // Keeps the key, salt and personalization.
+                self.state = self.params.to_state();
             }
         }
 
@@ -74,7 +112,7 @@ macro_rules! derive_hasher_blake {
 
 #[cfg(feature = "blake2b")]
 pub mod blake2b {
-    derive_hasher_blake!(blake2b_simd, Blake2bHasher);
+    derive_hasher_blake!(blake2b_simd, Blake2bHasher, crate::Blake2bCode);
 
     /// 256 bit blake2b hasher.
     pub type Blake2b256 = Blake2bHasher<32>;
@@ -85,7 +123,7 @@ pub mod blake2b {
 
 #[cfg(feature = "blake2s")]
 pub mod blake2s {
-    derive_hasher_blake!(blake2s_simd, Blake2sHasher);
+    derive_hasher_blake!(blake2s_simd, Blake2sHasher, crate::Blake2sCode);
 
     /// 256 bit blake2s hasher.
     pub type Blake2s128 = Blake2sHasher<16>;
@@ -104,6 +142,32 @@ pub mod blake3 {
     }
 
     impl<const S: usize> Blake3Hasher<S> {
+        /// Creates a hasher in keyed mode, e.g. to compute a MAC.
+        ///
+        /// Multicodec only assigns a code to plain BLAKE3 (`0x1e`), it doesn't tell whether a key
+        /// was used. As the digest can't be verified without the key, consider wrapping it with
+        /// a code from the private use range (`0x300000` to `0x3fffff`) instead, so that it isn't
+        /// mistaken for a plain BLAKE3 hash. Use `0x1e` only if all readers know about the key.
+        pub fn with_key(key: &[u8; ::blake3::KEY_LEN]) -> Self {
+            Self::from_hasher(::blake3::Hasher::new_keyed(key))
+        }
+
+        /// Creates a hasher in key derivation mode.
+        ///
+        /// The context string should be hardcoded, globally unique and application-specific.
+        /// The same considerations about the multihash code as for [`Blake3Hasher::with_key`]
+        /// apply.
+        pub fn with_derive_key(context: &str) -> Self {
+            Self::from_hasher(::blake3::Hasher::new_derive_key(context))
+        }
+
+        fn from_hasher(hasher: ::blake3::Hasher) -> Self {
+            Self {
+                hasher,
+                digest: [0; S],
+            }
+        }
+
         /// using blake3's XOF function, fills the given slice with hash output
         pub fn finalize_xof_fill(&mut self, digest_out: &mut [u8]) {
             let mut digest = self.hasher.finalize_xof();
@@ -113,16 +177,13 @@ pub mod blake3 {
 
     impl<const S: usize> Default for Blake3Hasher<S> {
         fn default() -> Self {
-            let hasher = ::blake3::Hasher::new();
-
-            Self {
-                hasher,
-                digest: [0; S],
-            }
+            Self::from_hasher(::blake3::Hasher::new())
         }
     }
 
     impl<const S: usize> multihash_derive::Hasher for Blake3Hasher<S> {
+        const OUTPUT_SIZE: Option<usize> = Some(S);
+
         fn update(&mut self, input: &[u8]) {
             self.hasher.update(input);
         }
@@ -134,6 +195,7 @@ pub mod blake3 {
         }
 
         fn reset(&mut self) {
+            // Keeps the key or the key derivation context.
             self.hasher.reset();
         }
     }
@@ -175,6 +237,8 @@ macro_rules! derive_rustcrypto_hasher {
         }
 
         impl ::multihash_derive::Hasher for $name {
+            const OUTPUT_SIZE: Option<usize> = Some($size);
+
             fn update(&mut self, input: &[u8]) {
                 use digest::Digest;
                 self.state.update(input)
@@ -224,8 +288,67 @@ pub mod sha1 {
 
 #[cfg(feature = "sha2")]
 pub mod sha2 {
+    derive_rustcrypto_hasher!(::sha2::Sha224, Sha2_224, 28);
     derive_rustcrypto_hasher!(::sha2::Sha256, Sha2_256, 32);
+    derive_rustcrypto_hasher!(::sha2::Sha384, Sha2_384, 48);
     derive_rustcrypto_hasher!(::sha2::Sha512, Sha2_512, 64);
+    derive_rustcrypto_hasher!(::sha2::Sha512_224, Sha2_512_224, 28);
+    derive_rustcrypto_hasher!(::sha2::Sha512_256, Sha2_512_256, 32);
+}
+
+#[cfg(feature = "sha3")]
+macro_rules! derive_hasher_shake {
+    ($module:ty, $name:ident) => {
+        /// Multihash hasher.
+        #[derive(Debug)]
+        pub struct $name<const S: usize> {
+            state: $module,
+            digest: [u8; S],
+        }
+
+        impl<const S: usize> $name<S> {
+            /// using the XOF function, fills the given slice with hash output
+            pub fn finalize_xof_fill(&mut self, digest_out: &mut [u8]) {
+                use digest::ExtendableOutput;
+                self.state.clone().finalize_xof_into(digest_out)
+            }
+        }
+
+        impl<const S: usize> Default for $name<S> {
+            fn default() -> Self {
+                Self {
+                    state: Default::default(),
+                    digest: [0; S],
+                }
+            }
+        }
+
+        impl<const S: usize> multihash_derive::Hasher for $name<S> {
+            const OUTPUT_SIZE: Option<usize> = Some(S);
+
+            fn update(&mut self, input: &[u8]) {
+                digest::Update::update(&mut self.state, input);
+            }
+
+            fn finalize(&mut self) -> &[u8] {
+                use digest::ExtendableOutput;
+                self.state.clone().finalize_xof_into(&mut self.digest);
+                &self.digest
+            }
+
+            fn reset(&mut self) {
+                digest::Reset::reset(&mut self.state);
+            }
+        }
+
+        impl<const S: usize> ::digest::Update for $name<S> {
+            fn update(&mut self, data: &[u8]) {
+                ::multihash_derive::Hasher::update(self, data)
+            }
+        }
+
+        derive_write!($name);
+    };
 }
 
 #[cfg(feature = "sha3")]
@@ -239,6 +362,15 @@ pub mod sha3 {
     derive_rustcrypto_hasher!(::sha3::Keccak256, Keccak256, 32);
     derive_rustcrypto_hasher!(::sha3::Keccak384, Keccak384, 48);
     derive_rustcrypto_hasher!(::sha3::Keccak512, Keccak512, 64);
+
+    derive_hasher_shake!(::sha3::Shake128, Shake128Hasher);
+    derive_hasher_shake!(::sha3::Shake256, Shake256Hasher);
+
+    /// shake-128 hasher with 256 bit output.
+    pub type Shake128 = Shake128Hasher<32>;
+
+    /// shake-256 hasher with 512 bit output.
+    pub type Shake256 = Shake256Hasher<64>;
 }
 
 #[cfg(feature = "ripemd")]
@@ -270,6 +402,8 @@ pub mod strobe {
     }
 
     impl<const S: usize> multihash_derive::Hasher for StrobeHasher<S> {
+        const OUTPUT_SIZE: Option<usize> = Some(S);
+
         fn update(&mut self, input: &[u8]) {
             self.strobe.ad(input, self.initialized);
             self.initialized = true;
@@ -10,10 +10,18 @@
 //! It is only recommended as a getting-started-quickly solution.
 //! For production, you should either define your own codetable or rely only on the `multihash` crate itself.
 
+#[cfg(any(feature = "blake2b", feature = "blake2s"))]
+mod blake2;
 mod hasher_impl;
 
 pub use multihash_derive::MultihashDigest;
 
+#[cfg(feature = "blake2b")]
+#[cfg_attr(docsrs, doc(cfg(feature = "blake2b")))]
+pub use crate::blake2::Blake2bCode;
+#[cfg(feature = "blake2s")]
+#[cfg_attr(docsrs, doc(cfg(feature = "blake2s")))]
+pub use crate::blake2::Blake2sCode;
 #[cfg(feature = "blake2b")]
 #[cfg_attr(docsrs, doc(cfg(feature = "blake2b")))]
 pub use crate::hasher_impl::blake2b::{Blake2b256, Blake2b512, Blake2bHasher};
@@ -31,11 +39,14 @@ pub use crate::hasher_impl::ripemd::{Ripemd160, Ripemd256, Ripemd320};
 pub use crate::hasher_impl::sha1::Sha1;
 #[cfg(feature = "sha2")]
 #[cfg_attr(docsrs, doc(cfg(feature = "sha2")))]
-pub use crate::hasher_impl::sha2::{Sha2_256, Sha2_512};
+pub use crate::hasher_impl::sha2::{
+    Sha2_224, Sha2_256, Sha2_384, Sha2_512, Sha2_512_224, Sha2_512_256,
+};
 #[cfg(feature = "sha3")]
 #[cfg_attr(docsrs, doc(cfg(feature = "sha3")))]
 pub use crate::hasher_impl::sha3::{
-    Keccak224, Keccak256, Keccak384, Keccak512, Sha3_224, Sha3_256, Sha3_384, Sha3_512,
+    Keccak224, Keccak256, Keccak384, Keccak512, Sha3_224, Sha3_256, Sha3_384, Sha3_512, Shake128,
+    Shake128Hasher, Shake256, Shake256Hasher,
 };
 #[cfg(feature = "strobe")]
 #[cfg_attr(docsrs, doc(cfg(feature = "strobe")))]
@@ -46,6 +57,9 @@ pub use crate::hasher_impl::strobe::{Strobe256, Strobe512, StrobeHasher};
 /// This is a default set of hashing algorithms. Usually applications would use their own subset of
 /// algorithms. See the [`multihash-derive`] crate for more information.
 ///
+/// BLAKE2 is only included with its common output lengths. For all other lengths see
+/// `Blake2bCode` and `Blake2sCode`.
+///
 /// [`multihash-derive`]: https://docs.rs/multihash-derive
 #[cfg_attr(feature = "arb", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -62,6 +76,26 @@ pub enum Code {
     #[cfg_attr(docsrs, doc(cfg(feature = "sha2")))]
     #[mh(code = 0x13, hasher = crate::Sha2_512)]
     Sha2_512,
+    /// SHA-224 (28-byte hash size)
+    #[cfg(feature = "sha2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sha2")))]
+    #[mh(code = 0x1013, hasher = crate::Sha2_224)]
+    Sha2_224,
+    /// SHA-384 (48-byte hash size)
+    #[cfg(feature = "sha2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sha2")))]
+    #[mh(code = 0x20, hasher = crate::Sha2_384)]
+    Sha2_384,
+    /// SHA-512/224 (28-byte hash size)
+    #[cfg(feature = "sha2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sha2")))]
+    #[mh(code = 0x1014, hasher = crate::Sha2_512_224)]
+    Sha2_512_224,
+    /// SHA-512/256 (32-byte hash size)
+    #[cfg(feature = "sha2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sha2")))]
+    #[mh(code = 0x1015, hasher = crate::Sha2_512_256)]
+    Sha2_512_256,
     /// SHA3-224 (28-byte hash size)
     #[cfg(feature = "sha3")]
     #[cfg_attr(docsrs, doc(cfg(feature = "sha3")))]
@@ -102,6 +136,16 @@ pub enum Code {
     #[cfg_attr(docsrs, doc(cfg(feature = "sha3")))]
     #[mh(code = 0x1d, hasher = crate::Keccak512)]
     Keccak512,
+    /// SHAKE-128 (32-byte hash size)
+    #[cfg(feature = "sha3")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sha3")))]
+    #[mh(code = 0x18, hasher = crate::Shake128)]
+    Shake128,
+    /// SHAKE-256 (64-byte hash size)
+    #[cfg(feature = "sha3")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sha3")))]
+    #[mh(code = 0x19, hasher = crate::Shake256)]
+    Shake256,
     /// BLAKE2b-256 (32-byte hash size)
     #[cfg(feature = "blake2b")]
     #[cfg_attr(docsrs, doc(cfg(feature = "blake2b")))]
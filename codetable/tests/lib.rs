@@ -2,8 +2,9 @@ use std::io::{Cursor, Write};
 
 use multihash_codetable::{
     Blake2b256, Blake2b512, Blake2s128, Blake2s256, Blake3_256, Keccak224, Keccak256, Keccak384,
-    Keccak512, Ripemd160, Ripemd256, Ripemd320, Sha1, Sha2_256, Sha2_512, Sha3_224, Sha3_256,
-    Sha3_384, Sha3_512, Strobe256, Strobe512,
+    Keccak512, Ripemd160, Ripemd256, Ripemd320, Sha1, Sha2_224, Sha2_256, Sha2_384, Sha2_512,
+    Sha2_512_224, Sha2_512_256, Sha3_224, Sha3_256, Sha3_384, Sha3_512, Shake128, Shake256,
+    Strobe256, Strobe512,
 };
 use multihash_derive::{Hasher, MultihashDigest};
 
@@ -16,6 +17,14 @@ pub enum Code {
     Sha2_256,
     #[mh(code = 0x13, hasher = Sha2_512)]
     Sha2_512,
+    #[mh(code = 0x1013, hasher = Sha2_224)]
+    Sha2_224,
+    #[mh(code = 0x20, hasher = Sha2_384)]
+    Sha2_384,
+    #[mh(code = 0x1014, hasher = Sha2_512_224)]
+    Sha2_512_224,
+    #[mh(code = 0x1015, hasher = Sha2_512_256)]
+    Sha2_512_256,
     #[mh(code = 0x17, hasher = Sha3_224)]
     Sha3_224,
     #[mh(code = 0x16, hasher = Sha3_256)]
@@ -32,6 +41,10 @@ pub enum Code {
     Keccak384,
     #[mh(code = 0x1d, hasher = Keccak512)]
     Keccak512,
+    #[mh(code = 0x18, hasher = Shake128)]
+    Shake128,
+    #[mh(code = 0x19, hasher = Shake256)]
+    Shake256,
     #[mh(code = 0xb220, hasher = Blake2b256)]
     Blake2b256,
     #[mh(code = 0xb240, hasher = Blake2b512)]
@@ -101,6 +114,12 @@ fn multihash_encode() {
         Sha2_256, Code::Sha2_256, b"helloworld", "1220936a185caaa266bb9cbe981e9e05cb78cd732b0b3280eb944412bb6f8f8f07af";
         Sha2_256, Code::Sha2_256, b"beep boop", "122090ea688e275d580567325032492b597bc77221c62493e76330b85ddda191ef7c";
         Sha2_512, Code::Sha2_512, b"hello world", "1340309ecc489c12d6eb4cc40f50c902f2b4d0ed77ee511a7c7a9bcd3ca86d4cd86f989dd35bc5ff499670da34255b45b0cfd830e81f605dcf7dc5542e93ae9cd76f";
+        Sha2_224, Code::Sha2_224, b"hello world", "93201c2f05477fc24bb4faefd86517156dafdecec45b8ad3cf2522a563582b";
+        Sha2_384, Code::Sha2_384, b"hello world", "2030fdbd8e75a67f29f701a4e040385e2e23986303ea10239211af907fcbb83578b3e417cb71ce646efd0819dd8c088de1bd";
+        Sha2_512_224, Code::Sha2_512_224, b"hello world", "94201c22e0d52336f64a998085078b05a6e37b26f8120f43bf4db4c43a64ee";
+        Sha2_512_256, Code::Sha2_512_256, b"hello world", "9520200ac561fac838104e3f2e4ad107b4bee3e938bf15f2b15f009ccccd61a913f017";
+        Sha2_224, Code::Sha2_224, b"beep boop", "93201c9c6cfd75d24643ebe9b4702243cb333881979be76678af4847c5969d";
+        Sha2_512_256, Code::Sha2_512_256, b"beep boop", "95202095706dc981f7c37f75f8732e2d2ca2579e6cbc5018f027ef51d8d1e56e2bc415";
         Sha3_224, Code::Sha3_224, b"hello world", "171Cdfb7f18c77e928bb56faeb2da27291bd790bc1045cde45f3210bb6c5";
         Sha3_256, Code::Sha3_256, b"hello world", "1620644bcc7e564373040999aac89e7622f3ca71fba1d972fd94a31c3bfbf24e3938";
         Sha3_384, Code::Sha3_384, b"hello world", "153083bff28dde1b1bf5810071c6643c08e5b05bdb836effd70b403ea8ea0a634dc4997eb1053aa3593f590f9c63630dd90b";
@@ -109,6 +128,8 @@ fn multihash_encode() {
         Keccak256, Code::Keccak256, b"hello world", "1B2047173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad";
         Keccak384, Code::Keccak384, b"hello world", "1C3065fc99339a2a40e99d3c40d695b22f278853ca0f925cde4254bcae5e22ece47e6441f91b6568425adc9d95b0072eb49f";
         Keccak512, Code::Keccak512, b"hello world", "1D403ee2b40047b8060f68c67242175660f4174d0af5c01d47168ec20ed619b0b7c42181f40aa1046f39e2ef9efc6910782a998e0013d172458957957fac9405b67d";
+        Shake128, Code::Shake128, b"hello world", "18203a9159f071e4dd1c8c4f968607c30942e120d8156b8b1e72e0d376e8871cb8b8";
+        Shake256, Code::Shake256, b"hello world", "1940369771bb2cb9d2b04c1d54cca487e372d9f187f73f7ba3f65b95c8ee7798c527f4f3c2d55c2d46a29f2e945d469c3df27853a8735271f5cc2d9e889544357116";
         Blake2b512, Code::Blake2b512, b"hello world", "c0e40240021ced8799296ceca557832ab941a50b4a11f83478cf141f51f933f653ab9fbcc05a037cddbed06e309bf334942c4e58cdf1a46e237911ccd7fcf9787cbc7fd0";
         Blake2s256, Code::Blake2s256, b"hello world", "e0e402209aec6806794561107e594b1f6a8a6b0c92a0cba9acf5e5e93cca06f781813b0b";
         Blake2b256, Code::Blake2b256, b"hello world", "a0e40220256c83b297114d201b30179f3f0ef0cace9783622da5974326b436178aeef610";
@@ -144,6 +165,10 @@ fn assert_decode() {
         Code::Sha2_256, "1220936a185caaa266bb9cbe981e9e05cb78cd732b0b3280eb944412bb6f8f8f07af";
         Code::Sha2_256, "122090ea688e275d580567325032492b597bc77221c62493e76330b85ddda191ef7c";
         Code::Sha2_512, "1340309ecc489c12d6eb4cc40f50c902f2b4d0ed77ee511a7c7a9bcd3ca86d4cd86f989dd35bc5ff499670da34255b45b0cfd830e81f605dcf7dc5542e93ae9cd76f";
+        Code::Sha2_224, "93201c2f05477fc24bb4faefd86517156dafdecec45b8ad3cf2522a563582b";
+        Code::Sha2_384, "2030fdbd8e75a67f29f701a4e040385e2e23986303ea10239211af907fcbb83578b3e417cb71ce646efd0819dd8c088de1bd";
+        Code::Sha2_512_224, "94201c22e0d52336f64a998085078b05a6e37b26f8120f43bf4db4c43a64ee";
+        Code::Sha2_512_256, "9520200ac561fac838104e3f2e4ad107b4bee3e938bf15f2b15f009ccccd61a913f017";
         Code::Sha3_224, "171Cdfb7f18c77e928bb56faeb2da27291bd790bc1045cde45f3210bb6c5";
         Code::Sha3_256, "1620644bcc7e564373040999aac89e7622f3ca71fba1d972fd94a31c3bfbf24e3938";
         Code::Sha3_384, "153083bff28dde1b1bf5810071c6643c08e5b05bdb836effd70b403ea8ea0a634dc4997eb1053aa3593f590f9c63630dd90b";
@@ -152,6 +177,8 @@ fn assert_decode() {
         Code::Keccak256, "1B2047173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad";
         Code::Keccak384, "1C3065fc99339a2a40e99d3c40d695b22f278853ca0f925cde4254bcae5e22ece47e6441f91b6568425adc9d95b0072eb49f";
         Code::Keccak512, "1D403ee2b40047b8060f68c67242175660f4174d0af5c01d47168ec20ed619b0b7c42181f40aa1046f39e2ef9efc6910782a998e0013d172458957957fac9405b67d";
+        Code::Shake128, "18203a9159f071e4dd1c8c4f968607c30942e120d8156b8b1e72e0d376e8871cb8b8";
+        Code::Shake256, "1940369771bb2cb9d2b04c1d54cca487e372d9f187f73f7ba3f65b95c8ee7798c527f4f3c2d55c2d46a29f2e945d469c3df27853a8735271f5cc2d9e889544357116";
         Code::Blake2b512, "c0e40240021ced8799296ceca557832ab941a50b4a11f83478cf141f51f933f653ab9fbcc05a037cddbed06e309bf334942c4e58cdf1a46e237911ccd7fcf9787cbc7fd0";
         Code::Blake2s256, "e0e402209aec6806794561107e594b1f6a8a6b0c92a0cba9acf5e5e93cca06f781813b0b";
         Code::Blake2b256, "a0e40220256c83b297114d201b30179f3f0ef0cace9783622da5974326b436178aeef610";
@@ -208,6 +235,10 @@ fn assert_roundtrip() {
         Code::Sha1, Sha1;
         Code::Sha2_256, Sha2_256;
         Code::Sha2_512, Sha2_512;
+        Code::Sha2_224, Sha2_224;
+        Code::Sha2_384, Sha2_384;
+        Code::Sha2_512_224, Sha2_512_224;
+        Code::Sha2_512_256, Sha2_512_256;
         Code::Sha3_224, Sha3_224;
         Code::Sha3_256, Sha3_256;
         Code::Sha3_384, Sha3_384;
@@ -216,6 +247,8 @@ fn assert_roundtrip() {
         Code::Keccak256, Keccak256;
         Code::Keccak384, Keccak384;
         Code::Keccak512, Keccak512;
+        Code::Shake128, Shake128;
+        Code::Shake256, Shake256;
         Code::Blake2b512, Blake2b512;
         Code::Blake2s256, Blake2s256;
         Code::Blake3_256, Blake3_256;
@@ -275,6 +308,26 @@ fn test_multihash_methods() {
       Code::Sha2_512,
      "1340",
      "309ecc489c12d6eb4cc40f50c902f2b4d0ed77ee511a7c7a9bcd3ca86d4cd86f989dd35bc5ff499670da34255b45b0cfd830e81f605dcf7dc5542e93ae9cd76f");
+    multihash_methods::<Sha2_224>(
+        Code::Sha2_224,
+        "93201c",
+        "2f05477fc24bb4faefd86517156dafdecec45b8ad3cf2522a563582b",
+    );
+    multihash_methods::<Sha2_384>(
+        Code::Sha2_384,
+        "2030",
+        "fdbd8e75a67f29f701a4e040385e2e23986303ea10239211af907fcbb83578b3e417cb71ce646efd0819dd8c088de1bd",
+    );
+    multihash_methods::<Sha2_512_224>(
+        Code::Sha2_512_224,
+        "94201c",
+        "22e0d52336f64a998085078b05a6e37b26f8120f43bf4db4c43a64ee",
+    );
+    multihash_methods::<Sha2_512_256>(
+        Code::Sha2_512_256,
+        "952020",
+        "0ac561fac838104e3f2e4ad107b4bee3e938bf15f2b15f009ccccd61a913f017",
+    );
     multihash_methods::<Sha3_224>(
         Code::Sha3_224,
         "171C",
@@ -311,6 +364,15 @@ fn test_multihash_methods() {
      Code::Keccak512,
      "1D40",
      "3ee2b40047b8060f68c67242175660f4174d0af5c01d47168ec20ed619b0b7c42181f40aa1046f39e2ef9efc6910782a998e0013d172458957957fac9405b67d");
+    multihash_methods::<Shake128>(
+        Code::Shake128,
+        "1820",
+        "3a9159f071e4dd1c8c4f968607c30942e120d8156b8b1e72e0d376e8871cb8b8",
+    );
+    multihash_methods::<Shake256, >(
+     Code::Shake256,
+     "1940",
+     "369771bb2cb9d2b04c1d54cca487e372d9f187f73f7ba3f65b95c8ee7798c527f4f3c2d55c2d46a29f2e945d469c3df27853a8735271f5cc2d9e889544357116");
     multihash_methods::<Blake2b512, >(
      Code::Blake2b512,
      "c0e40240",
@@ -423,3 +485,201 @@ fn blak3_non_default_digest() {
     let expected = hex::decode("aa51dcd43d5c6c5203ee16906fd6b35d").unwrap();
     assert_eq!(&content_hash.0, expected.as_slice())
 }
+
+#[test]
+fn shake_non_default_digest() {
+    use multihash_codetable::{Shake128Hasher, Shake256Hasher};
+
+    let mut hasher = Shake128Hasher::<16>::default();
+    hasher.update(b"hello world");
+    assert_eq!(
+        hasher.finalize(),
+        hex::decode("3a9159f071e4dd1c8c4f968607c30942").unwrap()
+    );
+
+    // The XOF output can be longer than the digest.
+    let mut output = [0; 100];
+    hasher.finalize_xof_fill(&mut output);
+    let expected = hex::decode("3a9159f071e4dd1c8c4f968607c30942e120d8156b8b1e72e0d376e8871cb8b899072665674f26cc494a4bcf027c58267e8ee2da60e942759de86d2670bba1aa47bffd20b48b1d2aa7c3349f8215d1b99ca65bdb1770a220f67456f602436032afce7f24").unwrap();
+    assert_eq!(&output[..], expected.as_slice());
+
+    hasher.reset();
+    hasher.update(b"hello world");
+    assert_eq!(hasher.finalize(), &expected[..16]);
+
+    let mut hasher = Shake256Hasher::<32>::default();
+    hasher.update(b"hello world");
+    assert_eq!(
+        hasher.finalize(),
+        hex::decode("369771bb2cb9d2b04c1d54cca487e372d9f187f73f7ba3f65b95c8ee7798c527").unwrap()
+    );
+}
+
+#[test]
+fn blake2_codes() {
+    use multihash_codetable::{Blake2bCode, Blake2bHasher, Blake2sCode, Blake2sHasher};
+
+    // The common lengths match the `Code` variants.
+    for (len, code) in [(32, Code::Blake2b256), (64, Code::Blake2b512)] {
+        let blake2b = Blake2bCode::new(len).unwrap();
+        assert_eq!(u64::from(blake2b), u64::from(code));
+        assert_eq!(blake2b.digest(b"hello world"), code.digest(b"hello world"));
+    }
+    for (len, code) in [(16, Code::Blake2s128), (32, Code::Blake2s256)] {
+        let blake2s = Blake2sCode::new(len).unwrap();
+        assert_eq!(u64::from(blake2s), u64::from(code));
+        assert_eq!(blake2s.digest(b"hello world"), code.digest(b"hello world"));
+    }
+
+    let blake2b = Blake2bCode::try_from(0xb219).unwrap();
+    assert_eq!(blake2b.len(), 25);
+    let mut hasher = Blake2bHasher::<25>::default();
+    hasher.update(b"hello world");
+    assert_eq!(
+        blake2b.digest(b"hello world"),
+        blake2b.wrap(hasher.finalize()).unwrap()
+    );
+    assert_eq!(
+        blake2b.digest(b"hello world").to_bytes(),
+        hex::decode("99e40219870d07a62f39e20c1f156d9e4f944d4be6a829bb6f3f00c4d9").unwrap()
+    );
+    assert_eq!(format!("{blake2b:?}"), "BLAKE2b-200");
+
+    let blake2s = Blake2sCode::try_from(0xb241).unwrap();
+    assert_eq!(blake2s.len(), 1);
+    let mut hasher = Blake2sHasher::<1>::default();
+    hasher.update(b"hello world");
+    assert_eq!(
+        blake2s.digest(b"hello world"),
+        blake2s.wrap(hasher.finalize()).unwrap()
+    );
+
+    // Every code in the ranges maps to a length and back.
+    for code in 0xb201..=0xb240 {
+        let blake2b = Blake2bCode::try_from(code).unwrap();
+        assert_eq!(Blake2bCode::new(blake2b.len()), Some(blake2b));
+        assert_eq!(blake2b.digest(b"").size() as usize, blake2b.len());
+        Blake2bCode::validate(&blake2b.digest(b"")).unwrap();
+    }
+    for code in 0xb241..=0xb260 {
+        let blake2s = Blake2sCode::try_from(code).unwrap();
+        assert_eq!(Blake2sCode::new(blake2s.len()), Some(blake2s));
+        assert_eq!(blake2s.digest(b"").size() as usize, blake2s.len());
+    }
+
+    assert!(Blake2bCode::try_from(0xb200).is_err());
+    assert!(Blake2bCode::try_from(0xb241).is_err());
+    assert!(Blake2sCode::try_from(0xb240).is_err());
+    assert!(Blake2sCode::try_from(0xb261).is_err());
+    assert_eq!(Blake2bCode::new(0), None);
+    assert_eq!(Blake2bCode::new(65), None);
+    assert_eq!(Blake2sCode::new(33), None);
+}
+
+#[test]
+fn blake2_keyed() {
+    use multihash_codetable::{Blake2bHasher, Blake2sHasher};
+
+    let mut hasher = Blake2b256::with_key(b"secret key");
+    hasher.update(b"hello world");
+    let expected = "612a5a7b4ac57f909bd3b43130676556620e13d2a86b55b028013751af06277d";
+    assert_eq!(hasher.finalize(), hex::decode(expected).unwrap());
+    assert_eq!(
+        hasher.finalize_multihash(),
+        Code::Blake2b256
+            .wrap(&hex::decode(expected).unwrap())
+            .unwrap()
+    );
+
+    // Resetting keeps the key.
+    hasher.reset();
+    hasher.update(b"hello world");
+    assert_eq!(hasher.finalize(), hex::decode(expected).unwrap());
+
+    // Zcash-style personalization.
+    let mut hasher = Blake2b512::with_params(&[], b"0123456789abcdef", b"ZcashPoW");
+    hasher.update(b"hello world");
+    assert_eq!(
+        hasher.finalize_multihash().to_bytes(),
+        hex::decode("c0e40240551d360143c05e3f60869efbb953363cf9a34cb09edb9f8e60301fac80a9170ae22b4f942dda9b0cc240cf077a3478e45eba32f126cc27336366d055040cf20a").unwrap()
+    );
+
+    let mut hasher = Blake2bHasher::<32>::with_params(b"secret key", b"salt", b"personal");
+    hasher.update(b"hello world");
+    assert_eq!(
+        hasher.finalize(),
+        hex::decode("ff1ccd4e3afedbfb6bc4e1739d461cb32e74731ed2f8427403de0119e9e58e78").unwrap()
+    );
+
+    let mut hasher = Blake2sHasher::<32>::with_params(b"secret key", b"salt", b"personal");
+    hasher.update(b"hello world");
+    assert_eq!(u64::from(hasher.code()), u64::from(Code::Blake2s256));
+    assert_eq!(
+        hasher.finalize(),
+        hex::decode("22cf6a1cdbdfe0d9cb444b148541f383179c8180dabc60a1185b700183d50a5f").unwrap()
+    );
+    hasher.reset();
+    hasher.update(b"hello world");
+    assert_eq!(
+        hasher.finalize(),
+        hex::decode("22cf6a1cdbdfe0d9cb444b148541f383179c8180dabc60a1185b700183d50a5f").unwrap()
+    );
+
+    let mut hasher = Blake2s128::with_key(b"secret key");
+    hasher.update(b"hello world");
+    assert_eq!(
+        hasher.finalize_multihash().to_bytes(),
+        hex::decode("d0e4021011cbff7b4fdc11415828cdc87be22e21").unwrap()
+    );
+
+    // Without parameters the hasher is the same as the default one.
+    let mut hasher = Blake2b256::with_params(&[], &[], &[]);
+    hasher.update(b"hello world");
+    assert_eq!(
+        hasher.finalize_multihash(),
+        Code::Blake2b256.digest(b"hello world")
+    );
+}
+
+#[test]
+fn blake3_keyed_and_derive_key() {
+    use multihash_codetable::Blake3Hasher;
+
+    let key = *b"whats the Elvish word for friend";
+    let mut hasher = Blake3_256::with_key(&key);
+    hasher.update(b"hello world");
+    let expected = blake3::keyed_hash(&key, b"hello world");
+    assert_eq!(hasher.finalize(), expected.as_bytes());
+    assert_ne!(
+        hasher.finalize(),
+        Code::Blake3_256.digest(b"hello world").digest()
+    );
+
+    // Resetting keeps the key.
+    hasher.reset();
+    hasher.update(b"hello world");
+    assert_eq!(hasher.finalize(), expected.as_bytes());
+
+    let context = "multihash-codetable test vectors context";
+    let mut hasher = Blake3Hasher::<64>::with_derive_key(context);
+    hasher.update(b"key material");
+    let mut expected = [0; 64];
+    blake3::Hasher::new_derive_key(context)
+        .update(b"key material")
+        .finalize_xof()
+        .fill(&mut expected);
+    assert_eq!(hasher.finalize(), expected);
+    assert_eq!(
+        &hasher.finalize()[..32],
+        blake3::derive_key(context, b"key material")
+    );
+
+    // Resetting keeps the context.
+    hasher.reset();
+    hasher.update(b"key material");
+    assert_eq!(hasher.finalize(), expected);
+
+    // Keyed digests can be wrapped with a private use code.
+    let mh = Multihash::wrap(0x300000, hasher.finalize()).unwrap();
+    assert_eq!(mh.code(), 0x300000);
+}
@@ -6,6 +6,7 @@
 
 extern crate proc_macro;
 
+mod literal;
 mod multihash;
 mod utils;
 
@@ -33,3 +34,11 @@ pub fn MultihashDigest(i: TokenStream) -> TokenStream {
     #[allow(deprecated)]
     Multihash(i)
 }
+
+/// Creates a `Multihash` from a hex or multibase encoded string literal at compile time.
+///
+/// See `multihash_derive::multihash!` for details.
+#[proc_macro]
+pub fn multihash(input: TokenStream) -> TokenStream {
+    literal::multihash(input.into()).into()
+}
@@ -0,0 +1,130 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+
+use crate::utils;
+
+/// The input of the `multihash!` macro, a string literal optionally followed by the size.
+struct Input {
+    literal: syn::LitStr,
+    size: Option<syn::Expr>,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let literal = input.parse()?;
+        let size = if input.is_empty() {
+            None
+        } else {
+            input.parse::<syn::token::Comma>()?;
+            Some(input.parse()?)
+        };
+        Ok(Self { literal, size })
+    }
+}
+
+/// Decodes a hex string without the `0x` prefix, returns `None` if it isn't one.
+fn decode_hex(string: &str) -> Option<Vec<u8>> {
+    // `u8::from_str_radix` accepts a sign, so check the digits upfront.
+    if string.len() % 2 != 0 || !string.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    string
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = core::str::from_utf8(pair).ok()?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+/// Decodes the literal into the bytes of a multihash and the size of its digest.
+///
+/// Strings starting with `0x` are decoded as hex, anything else as multibase.
+fn decode(literal: &syn::LitStr) -> syn::Result<(Vec<u8>, usize)> {
+    let string = literal.value();
+    let bytes = match string.strip_prefix("0x") {
+        Some(hex) => decode_hex(hex).ok_or_else(|| {
+            syn::Error::new(
+                literal.span(),
+                "the multihash is not hex encoded, expected an even number of hex digits after `0x`",
+            )
+        })?,
+        None => multibase::decode(&string)
+            .map(|(_base, bytes)| bytes)
+            .map_err(|err| {
+                syn::Error::new(
+                    literal.span(),
+                    format!("the multihash is not multibase encoded, hex needs a `0x` prefix: {err}"),
+                )
+            })?,
+    };
+    let multihash = multihash::Multihash::<{ u8::MAX as usize }>::from_bytes(&bytes)
+        .map_err(|err| syn::Error::new(literal.span(), format!("invalid multihash: {err}")))?;
+    Ok((bytes, multihash.size().into()))
+}
+
+pub fn multihash(input: TokenStream) -> TokenStream {
+    match multihash_inner(input) {
+        Ok(ts) => ts,
+        Err(e) => e.to_compile_error(),
+    }
+}
+
+fn multihash_inner(input: TokenStream) -> syn::Result<TokenStream> {
+    let mh_crate =
+        utils::use_crate("multihash-derive").map_err(|e| syn::Error::new(Span::call_site(), e))?;
+    let Input { literal, size } = syn::parse2(input)?;
+    let (bytes, digest_size) = decode(&literal)?;
+    // Check literal sizes right away, other sizes are only known when the constant is evaluated.
+    if let Some(syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Int(size),
+        ..
+    })) = &size
+    {
+        if digest_size > size.base10_parse()? {
+            return Err(syn::Error::new(
+                size.span(),
+                format!(
+                    "the digest of {digest_size} bytes doesn't fit into a multihash of size {size}"
+                ),
+            ));
+        }
+    }
+    let ty = match size {
+        Some(size) => quote!(#mh_crate::Multihash::<{ #size }>),
+        None => quote!(#mh_crate::Multihash),
+    };
+    // The const block makes sure that a too small size is a compile-time error.
+    Ok(quote! {
+        const { #ty::from_bytes_const(&[#(#bytes),*]) }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let expected = vec![0x12, 0x03, 0x01, 0x02, 0x03];
+        let hex: syn::LitStr = syn::parse_quote!("0x1203010203");
+        assert_eq!(decode(&hex).unwrap(), (expected.clone(), 3));
+        let base32: syn::LitStr = syn::parse_quote!("bcibqcaqd");
+        assert_eq!(decode(&base32).unwrap(), (expected, 3));
+
+        // Base32 that is valid hex as well is never mistaken for hex.
+        let base32: syn::LitStr = syn::parse_quote!("baaa2a");
+        assert_eq!(decode(&base32).unwrap(), (vec![0x00, 0x01, 0xa0], 1));
+
+        let trailing: syn::LitStr = syn::parse_quote!("0x120301020304");
+        assert!(decode(&trailing).is_err());
+        let signed: syn::LitStr = syn::parse_quote!("0x12+0");
+        assert!(decode(&signed).is_err());
+        let unprefixed: syn::LitStr = syn::parse_quote!("1203010203");
+        assert!(decode(&unprefixed).is_err());
+        let invalid: syn::LitStr = syn::parse_quote!("!1203");
+        assert!(decode(&invalid).is_err());
+    }
+}
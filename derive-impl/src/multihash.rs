@@ -15,6 +15,8 @@ mod kw {
     custom_keyword!(hasher);
     custom_keyword!(mh);
     custom_keyword!(alloc_size);
+    custom_keyword!(digest_size);
+    custom_keyword!(min_digest_size);
 }
 
 /// Attributes for the enum items.
@@ -23,6 +25,8 @@ mod kw {
 enum MhAttr {
     Code(utils::Attr<kw::code, syn::Expr>),
     Hasher(utils::Attr<kw::hasher, Box<syn::Type>>),
+    DigestSize(utils::Attr<kw::digest_size, syn::Expr>),
+    MinDigestSize(utils::Attr<kw::min_digest_size, syn::Expr>),
 }
 
 impl Parse for MhAttr {
@@ -31,6 +35,10 @@ impl Parse for MhAttr {
             Ok(MhAttr::Code(input.parse()?))
         } else if input.peek(kw::hasher) {
             Ok(MhAttr::Hasher(input.parse()?))
+        } else if input.peek(kw::digest_size) {
+            Ok(MhAttr::DigestSize(input.parse()?))
+        } else if input.peek(kw::min_digest_size) {
+            Ok(MhAttr::MinDigestSize(input.parse()?))
         } else {
             Err(syn::Error::new(input.span(), "unknown attribute"))
         }
@@ -55,6 +63,7 @@ impl Parse for DeriveAttr {
 
 struct Params {
     code_enum: syn::Ident,
+    mh_crate: syn::Ident,
 }
 
 #[derive(Debug)]
@@ -62,6 +71,8 @@ struct Hash {
     ident: syn::Ident,
     code: syn::Expr,
     hasher: Box<syn::Type>,
+    digest_size: Option<syn::Expr>,
+    min_digest_size: Option<syn::Expr>,
 }
 
 impl Hash {
@@ -88,6 +99,25 @@ impl Hash {
             Multihash::wrap(#code, hasher.finalize()).unwrap()
         })
     }
+
+    fn code_digest_size(&self, params: &Params) -> TokenStream {
+        let ident = &self.ident;
+        let mh_crate = &params.mh_crate;
+        match &self.digest_size {
+            Some(digest_size) => quote!(Self::#ident => Some(#digest_size)),
+            None => {
+                let hasher = &self.hasher;
+                quote!(Self::#ident => <#hasher as #mh_crate::Hasher>::OUTPUT_SIZE)
+            }
+        }
+    }
+
+    fn code_min_digest_size(&self) -> Option<TokenStream> {
+        let ident = &self.ident;
+        self.min_digest_size
+            .as_ref()
+            .map(|min_digest_size| quote!(Self::#ident => #min_digest_size))
+    }
 }
 
 impl<'a> TryFrom<&'a VariantInfo<'a>> for Hash {
@@ -95,6 +125,8 @@ impl<'a> TryFrom<&'a VariantInfo<'a>> for Hash {
     fn try_from(bi: &'a VariantInfo<'a>) -> Result<Self, syn::Error> {
         let mut code = None;
         let mut hasher = None;
+        let mut digest_size = None;
+        let mut min_digest_size = None;
         for attr in bi.ast().attrs {
             let attr: Result<utils::Attrs<MhAttr>, _> = syn::parse2(attr.meta.to_token_stream());
             if let Ok(attr) = attr {
@@ -102,6 +134,8 @@ impl<'a> TryFrom<&'a VariantInfo<'a>> for Hash {
                     match attr {
                         MhAttr::Code(attr) => code = Some(attr.value),
                         MhAttr::Hasher(attr) => hasher = Some(attr.value),
+                        MhAttr::DigestSize(attr) => digest_size = Some(attr.value),
+                        MhAttr::MinDigestSize(attr) => min_digest_size = Some(attr.value),
                     }
                 }
             }
@@ -126,6 +160,8 @@ impl<'a> TryFrom<&'a VariantInfo<'a>> for Hash {
             ident,
             code,
             hasher,
+            digest_size,
+            min_digest_size,
         })
     }
 }
@@ -216,11 +252,30 @@ fn multihash_inner(s: Structure) -> syn::Result<TokenStream> {
 
     let params = Params {
         code_enum: code_enum.clone(),
+        mh_crate: mh_crate.clone(),
     };
 
     let code_into_u64 = hashes.iter().map(|h| h.code_into_u64(&params));
     let code_from_u64 = hashes.iter().map(|h| h.code_from_u64());
     let code_digest = hashes.iter().map(|h| h.code_digest());
+    let code_digest_size = hashes.iter().map(|h| h.code_digest_size(&params));
+    let code_min_digest_size: Vec<_> = hashes
+        .iter()
+        .filter_map(|h| h.code_min_digest_size())
+        .collect();
+    // Only override the default implementation if any minimum was set.
+    let min_digest_size = if code_min_digest_size.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            fn min_digest_size(&self) -> usize {
+                match self {
+                    #(#code_min_digest_size,)*
+                    _ => #mh_crate::MultihashDigest::digest_size(self).unwrap_or(0),
+                }
+            }
+        }
+    };
 
     Ok(quote! {
         /// A Multihash with the same allocated size as the Multihashes produces by this derive.
@@ -238,6 +293,15 @@ fn multihash_inner(s: Structure) -> syn::Result<TokenStream> {
             fn wrap(&self, digest: &[u8]) -> Result<Multihash, #mh_crate::Error> {
                 Multihash::wrap((*self).into(), digest)
             }
+
+            fn digest_size(&self) -> Option<usize> {
+                match self {
+                    #(#code_digest_size,)*
+                    _ => unreachable!(),
+                }
+            }
+
+            #min_digest_size
         }
 
         impl From<#code_enum> for u64 {
@@ -1,5 +1,11 @@
 /// Trait implemented by a hash function implementation.
 pub trait Hasher {
+    /// The size of the digests [`Hasher::finalize`] returns.
+    ///
+    /// It's `None` if the size is variable, e.g. for the identity hash, or unknown. The
+    /// [`MultihashDigest`](crate::MultihashDigest) derive uses it to validate digest sizes.
+    const OUTPUT_SIZE: Option<usize> = None;
+
     /// Consume input and update internal state.
     fn update(&mut self, input: &[u8]);
 
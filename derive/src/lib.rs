@@ -19,6 +19,13 @@
 //! can be useful if you e.g. have specified type aliases for your hash digests and you are sure
 //! you use the correct value for `alloc_size`.
 //!
+//! Parsed multihashes can be checked against the code table with [`MultihashDigest::validate`],
+//! which requires the digest to have the size the hasher produces, as given by
+//! [`Hasher::OUTPUT_SIZE`]. It can be set explicitly with `#[mh(digest_size = …)]` on the variant,
+//! e.g. if the hasher doesn't declare it. If a code may be used with truncated digests, set the
+//! smallest allowed size with `#[mh(min_digest_size = …)]` and use
+//! [`MultihashDigest::validate_truncated`].
+//!
 //! When you want to define your own codetable, you should only depend on `multihash-derive`.
 //! It re-exports the `multihash` crate for you.
 //!
@@ -62,12 +69,31 @@ use core::convert::TryFrom;
 use core::fmt;
 
 pub use hasher::Hasher;
-pub use multihash::Error;
 pub use multihash::Multihash;
+pub use multihash::{Error, ErrorKind};
 #[doc(inline)]
 pub use multihash_derive_impl::Multihash; // This one is deprecated.
 pub use multihash_derive_impl::MultihashDigest;
 
+/// Creates a [`Multihash`] from a string literal at compile time.
+///
+/// The literal is either the hex encoding of the multihash bytes prefixed with `0x`, or a
+/// [multibase] string. Malformed input results in a compile error pointing at the literal. The
+/// size of the multihash is inferred, it can also be given explicitly as second argument.
+///
+/// # Example
+///
+/// ```ignore : proc-macro-crate does not work in docs, see https://github.com/bkchr/proc-macro-crate/issues/14
+/// use multihash_derive::{multihash, Multihash};
+///
+/// const HEX: Multihash<32> = multihash!("0x1203010203");
+/// let multibase = multihash!("bcibqcaqd", 64);
+/// assert_eq!(HEX, multibase);
+/// ```
+///
+/// [multibase]: https://github.com/multiformats/multibase
+pub use multihash_derive_impl::multihash;
+
 /// The given code is not supported by this codetable.
 #[derive(Debug)]
 pub struct UnsupportedCode(pub u64);
@@ -80,6 +106,12 @@ impl fmt::Display for UnsupportedCode {
 
 impl core::error::Error for UnsupportedCode {}
 
+impl From<UnsupportedCode> for Error {
+    fn from(err: UnsupportedCode) -> Self {
+        ErrorKind::UnsupportedCode { code: err.0 }.into()
+    }
+}
+
 /// Trait that implements hashing.
 ///
 /// Typically, you won't implement this yourself but use the [`MultihashDigest`](multihash_derive_impl::MultihashDigest) custom-derive.
@@ -98,5 +130,86 @@ pub trait MultihashDigest<const S: usize>:
     fn digest(&self, input: &[u8]) -> Multihash<S>;
 
     /// Create a multihash from an existing multihash digest.
+    ///
+    /// The size of the digest isn't checked, use [`MultihashDigest::validate`] for that.
     fn wrap(&self, digest: &[u8]) -> Result<Multihash<S>, Error>;
+
+    /// Hashes the data and checks whether the result matches the expected multihash.
+    ///
+    /// The digests are compared in constant time, see [`Multihash::eq_constant_time`]. It returns
+    /// `false` if the expected multihash has a different code.
+    fn verify(&self, expected: &Multihash<S>, data: &[u8]) -> bool {
+        self.digest(data).eq_constant_time(expected)
+    }
+
+    /// Returns the size of the digests the hasher of this code produces.
+    ///
+    /// It's `None` if the size is variable, e.g. for the identity hash, then digests of any size
+    /// are valid. The derive returns the `#[mh(digest_size = …)]` attribute of the variant or the
+    /// [`Hasher::OUTPUT_SIZE`] of its hasher.
+    fn digest_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the minimum digest size [`MultihashDigest::validate_truncated`] accepts.
+    ///
+    /// It can be set per code with the `#[mh(min_digest_size = …)]` attribute. By default it's
+    /// the same as [`MultihashDigest::digest_size`], hence truncated digests are rejected.
+    fn min_digest_size(&self) -> usize {
+        self.digest_size().unwrap_or(0)
+    }
+
+    /// Checks that the code of the multihash is part of this code table and that the digest has
+    /// exactly the size the hasher of that code produces.
+    ///
+    /// Returns the code of the multihash.
+    fn validate(multihash: &Multihash<S>) -> Result<Self, Error> {
+        let code = Self::try_from(multihash.code())?;
+        if let Some(size) = code.digest_size() {
+            check_digest_size(multihash, size, size)?;
+        }
+        Ok(code)
+    }
+
+    /// Same as [`MultihashDigest::validate`], but also accepts digests that were truncated down to
+    /// the [`MultihashDigest::min_digest_size`] of the code.
+    fn validate_truncated(multihash: &Multihash<S>) -> Result<Self, Error> {
+        let code = Self::try_from(multihash.code())?;
+        let max = code.digest_size().unwrap_or(S);
+        check_digest_size(multihash, code.min_digest_size(), max)?;
+        Ok(code)
+    }
+
+    /// Parses a multihash from bytes and [validates](MultihashDigest::validate) it.
+    fn parse_strict(bytes: &[u8]) -> Result<Multihash<S>, Error> {
+        let multihash = Multihash::from_bytes(bytes)?;
+        Self::validate(&multihash)?;
+        Ok(multihash)
+    }
+
+    /// Parses a multihash from bytes and [validates](MultihashDigest::validate_truncated) it,
+    /// accepting truncated digests.
+    fn parse_truncated(bytes: &[u8]) -> Result<Multihash<S>, Error> {
+        let multihash = Multihash::from_bytes(bytes)?;
+        Self::validate_truncated(&multihash)?;
+        Ok(multihash)
+    }
+}
+
+/// Returns an error if the digest size isn't within `min..=max`.
+fn check_digest_size<const S: usize>(
+    multihash: &Multihash<S>,
+    min: usize,
+    max: usize,
+) -> Result<(), Error> {
+    let size = usize::from(multihash.size());
+    if size < min || size > max {
+        return Err(ErrorKind::DigestSizeMismatch {
+            size: multihash.size().into(),
+            min,
+            max,
+        }
+        .into());
+    }
+    Ok(())
 }
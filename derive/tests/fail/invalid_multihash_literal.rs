@@ -0,0 +1,5 @@
+fn main() {
+    let _: multihash_derive::Multihash<32> = multihash_derive::multihash!("0x120301020304");
+    let _: multihash_derive::Multihash<32> = multihash_derive::multihash!("0x12030102030");
+    let _: multihash_derive::Multihash<32> = multihash_derive::multihash!("1203010203");
+}
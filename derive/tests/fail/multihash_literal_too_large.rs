@@ -0,0 +1,3 @@
+fn main() {
+    let _ = multihash_derive::multihash!("0x1203010203", 2);
+}
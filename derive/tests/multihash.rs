@@ -27,3 +27,139 @@ fn uses_correct_hasher() {
 
     assert_eq!(multihash1, multihash2)
 }
+
+#[test]
+fn verify() {
+    #[derive(Clone, Debug, Eq, PartialEq, Copy, MultihashDigest)]
+    #[mh(alloc_size = 64)]
+    pub enum Code {
+        #[mh(code = 0x38b64f, hasher = multihash_codetable::Strobe256)]
+        Strobe256,
+        #[mh(code = 0x3312e8, hasher = multihash_codetable::Strobe512)]
+        Strobe512,
+    }
+
+    let expected = Code::Strobe256.digest(b"foobar");
+    assert!(Code::Strobe256.verify(&expected, b"foobar"));
+    assert!(!Code::Strobe256.verify(&expected, b"foobaz"));
+    assert!(!Code::Strobe512.verify(&expected, b"foobar"));
+}
+
+#[test]
+fn unsupported_code_into_error() {
+    use multihash_derive::{ErrorKind, UnsupportedCode};
+
+    let err = multihash_derive::Error::from(UnsupportedCode(0x99));
+    assert_eq!(err.kind(), ErrorKind::UnsupportedCode { code: 0x99 });
+}
+
+#[test]
+fn validate_digest_size() {
+    use multihash_derive::ErrorKind;
+
+    #[derive(Clone, Debug, Eq, PartialEq, Copy, MultihashDigest)]
+    #[mh(alloc_size = 64)]
+    pub enum Code {
+        #[mh(code = 0x38b64f, hasher = multihash_codetable::Strobe256)]
+        Strobe256,
+        #[mh(code = 0x3312e8, hasher = multihash_codetable::Strobe512, min_digest_size = 20)]
+        Strobe512,
+    }
+
+    assert_eq!(Code::Strobe256.digest_size(), Some(32));
+    assert_eq!(Code::Strobe256.min_digest_size(), 32);
+    assert_eq!(Code::Strobe512.min_digest_size(), 20);
+
+    let full = Code::Strobe512.digest(b"foobar");
+    assert_eq!(Code::validate(&full).unwrap(), Code::Strobe512);
+    assert_eq!(Code::parse_strict(&full.to_bytes()).unwrap(), full);
+
+    // Truncated digests are only accepted on request and not below the minimum.
+    let truncated = Code::Strobe512.wrap(&full.digest()[..20]).unwrap();
+    let err = Code::validate(&truncated).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        ErrorKind::DigestSizeMismatch {
+            size: 20,
+            min: 64,
+            max: 64
+        }
+    );
+    assert_eq!(
+        Code::validate_truncated(&truncated).unwrap(),
+        Code::Strobe512
+    );
+    assert_eq!(
+        Code::parse_truncated(&truncated.to_bytes()).unwrap(),
+        truncated
+    );
+    let too_short = Code::Strobe512.wrap(&full.digest()[..19]).unwrap();
+    assert!(Code::validate_truncated(&too_short).is_err());
+    let strobe_truncated = Code::Strobe256.wrap(&[0; 20]).unwrap();
+    assert!(Code::validate_truncated(&strobe_truncated).is_err());
+
+    let unknown = Multihash::wrap(0x99, &[0; 32]).unwrap();
+    assert_eq!(
+        Code::validate(&unknown).unwrap_err().kind(),
+        ErrorKind::UnsupportedCode { code: 0x99 }
+    );
+}
+
+#[test]
+fn validate_variable_digest_size() {
+    #[derive(Default)]
+    struct IdentityHasher(Vec<u8>);
+
+    impl Hasher for IdentityHasher {
+        fn update(&mut self, input: &[u8]) {
+            self.0.extend_from_slice(input);
+        }
+
+        fn finalize(&mut self) -> &[u8] {
+            &self.0
+        }
+
+        fn reset(&mut self) {
+            self.0.clear();
+        }
+    }
+
+    #[derive(Clone, Debug, Eq, PartialEq, Copy, MultihashDigest)]
+    #[mh(alloc_size = 64)]
+    pub enum Code {
+        #[mh(code = 0x00, hasher = IdentityHasher)]
+        Identity,
+        #[mh(code = 0x38b64f, hasher = IdentityHasher, digest_size = 32)]
+        FixedIdentity,
+    }
+
+    assert_eq!(Code::Identity.digest_size(), None);
+    assert_eq!(Code::Identity.min_digest_size(), 0);
+    assert_eq!(Code::FixedIdentity.digest_size(), Some(32));
+
+    // Identity multihashes of any size are valid.
+    for input in [&b""[..], b"foobar", &[7; 64]] {
+        let identity = Code::Identity.digest(input);
+        assert_eq!(Code::parse_strict(&identity.to_bytes()).unwrap(), identity);
+        assert_eq!(Code::validate_truncated(&identity).unwrap(), Code::Identity);
+    }
+
+    // An explicit size is checked, even if the hasher doesn't declare one.
+    let fixed = Code::FixedIdentity.digest(b"foobar");
+    assert!(Code::parse_strict(&fixed.to_bytes()).is_err());
+    assert!(Code::validate(&Code::FixedIdentity.digest(&[7; 32])).is_ok());
+}
+
+#[test]
+fn multihash_literal() {
+    use multihash_derive::{multihash, Multihash};
+
+    const HEX: Multihash<32> = multihash!("0x1203010203");
+    let expected = Multihash::<32>::wrap(0x12, &[0x01, 0x02, 0x03]).unwrap();
+    assert_eq!(HEX, expected);
+
+    let base32 = multihash!("bcibqcaqd", 64);
+    assert_eq!(base32, expected);
+    let base58btc: Multihash<32> = multihash!("z32s2F3p");
+    assert_eq!(base58btc, expected);
+}
@@ -0,0 +1,348 @@
+//! Multihash reading and writing over async byte streams.
+//!
+//! The `futures-io` feature adds `read_futures_io()`/`write_futures_io()` methods for the
+//! [`futures_io::AsyncRead`]/[`futures_io::AsyncWrite`] traits, the `tokio` feature adds
+//! `read_tokio()`/`write_tokio()` methods for the [`tokio::io::AsyncRead`]/
+//! [`tokio::io::AsyncWrite`] traits. The futures are `Send` if the stream is.
+//!
+//! [`MultihashRef`] only has the write methods, as it borrows its digest it can't be read from a
+//! stream. Read a [`Multihash`] or a `MultihashBuf` instead.
+
+use std::io;
+
+use unsigned_varint::{decode, encode as varint_encode};
+
+use crate::multihash::varint_len;
+#[cfg(feature = "alloc")]
+use crate::MultihashBuf;
+use crate::{Error, Multihash, MultihashRef};
+
+/// Abstraction over the async read traits of the different runtimes.
+trait AsyncReadExact {
+    async fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()>;
+}
+
+/// Abstraction over the async write traits of the different runtimes.
+trait AsyncWriteAll {
+    async fn write_all(&mut self, buf: &[u8]) -> io::Result<()>;
+}
+
+#[cfg(feature = "futures-io")]
+struct FuturesIo<T>(T);
+
+#[cfg(feature = "futures-io")]
+impl<R: futures_io::AsyncRead + Unpin> AsyncReadExact for FuturesIo<R> {
+    async fn read_exact(&mut self, mut buf: &mut [u8]) -> io::Result<()> {
+        use core::{future::poll_fn, pin::Pin};
+
+        while !buf.is_empty() {
+            match poll_fn(|cx| Pin::new(&mut self.0).poll_read(cx, buf)).await {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => buf = &mut buf[n..],
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<W: futures_io::AsyncWrite + Unpin> AsyncWriteAll for FuturesIo<W> {
+    async fn write_all(&mut self, mut buf: &[u8]) -> io::Result<()> {
+        use core::{future::poll_fn, pin::Pin};
+
+        while !buf.is_empty() {
+            match poll_fn(|cx| Pin::new(&mut self.0).poll_write(cx, buf)).await {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => buf = &buf[n..],
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+struct TokioIo<T>(T);
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncRead + Unpin> AsyncReadExact for TokioIo<R> {
+    async fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        tokio::io::AsyncReadExt::read_exact(&mut self.0, buf).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<W: tokio::io::AsyncWrite + Unpin> AsyncWriteAll for TokioIo<W> {
+    async fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        tokio::io::AsyncWriteExt::write_all(&mut self.0, buf).await
+    }
+}
+
+/// Reads a varint encoded `u64`, the async equivalent of [`crate::multihash::read_u64`].
+async fn read_u64<R: AsyncReadExact>(r: &mut R) -> Result<u64, Error> {
+    let mut b = varint_encode::u64_buffer();
+    for i in 0..b.len() {
+        r.read_exact(&mut b[i..i + 1])
+            .await
+            .map_err(crate::error::io_to_multihash_error)?;
+        if decode::is_last(b[i]) {
+            break;
+        }
+    }
+    // Decoding reports the overflow in case the last byte wasn't reached.
+    decode::u64(&b)
+        .map(|decoded| decoded.0)
+        .map_err(crate::error::unsigned_varint_decode_to_multihash_error)
+}
+
+/// Reads the code and the size of a multihash, leaving the digest unread.
+///
+/// The async equivalent of [`crate::multihash::read_multihash_header`].
+async fn read_multihash_header<R: AsyncReadExact>(
+    r: &mut R,
+    max_size: usize,
+) -> Result<(u64, usize, usize), Error> {
+    let code = read_u64(r).await.map_err(|err| err.at(0))?;
+    let code_len = varint_len(code);
+    let size = read_u64(r).await.map_err(|err| err.at(code_len))?;
+    if size > max_size as u64 {
+        return Err(Error::digest_too_large(size, max_size).at(code_len));
+    }
+    Ok((code, size as usize, code_len + varint_len(size)))
+}
+
+async fn read_multihash<R: AsyncReadExact, const S: usize>(
+    mut r: R,
+) -> Result<Multihash<S>, Error> {
+    let (code, size, header_len) = read_multihash_header(&mut r, S.min(u8::MAX as usize)).await?;
+
+    let mut digest = [0; S];
+    r.read_exact(&mut digest[..size])
+        .await
+        .map_err(|err| crate::error::io_to_multihash_error(err).at(header_len))?;
+    Multihash::wrap(code, &digest[..size])
+}
+
+#[cfg(feature = "alloc")]
+async fn read_multihash_buf<R: AsyncReadExact>(
+    mut r: R,
+    max_size: usize,
+) -> Result<MultihashBuf, Error> {
+    let (code, size, header_len) = read_multihash_header(&mut r, max_size).await?;
+
+    let mut digest = vec![0; size];
+    r.read_exact(&mut digest)
+        .await
+        .map_err(|err| crate::error::io_to_multihash_error(err).at(header_len))?;
+    Ok(MultihashBuf::wrap(code, digest))
+}
+
+async fn write_multihash<W: AsyncWriteAll>(
+    mut w: W,
+    code: u64,
+    digest: &[u8],
+) -> Result<usize, Error> {
+    let mut code_buf = varint_encode::u64_buffer();
+    let code = varint_encode::u64(code, &mut code_buf);
+
+    let mut size_buf = varint_encode::u64_buffer();
+    let size = varint_encode::u64(digest.len() as u64, &mut size_buf);
+
+    let written = code.len() + size.len() + digest.len();
+
+    w.write_all(code)
+        .await
+        .map_err(crate::error::io_to_multihash_error)?;
+    w.write_all(size)
+        .await
+        .map_err(crate::error::io_to_multihash_error)?;
+    w.write_all(digest)
+        .await
+        .map_err(crate::error::io_to_multihash_error)?;
+
+    Ok(written)
+}
+
+macro_rules! impl_async_io {
+    ($wrapper:ident, $read_trait:path, $write_trait:path, $read:ident, $write:ident) => {
+        impl<const S: usize> Multihash<S> {
+            /// Reads a multihash from an async byte stream.
+            ///
+            /// The same validation as in [`Multihash::read`] is applied.
+            pub async fn $read<R: $read_trait + Unpin>(r: R) -> Result<Self, Error> {
+                read_multihash($wrapper(r)).await
+            }
+
+            /// Writes a multihash to an async byte stream, returning the written size.
+            pub async fn $write<W: $write_trait + Unpin>(&self, w: W) -> Result<usize, Error> {
+                write_multihash($wrapper(w), self.code(), self.digest()).await
+            }
+        }
+
+        /// There is no read method, a borrowed multihash can't be read from a stream.
+        impl MultihashRef<'_> {
+            /// Writes a multihash to an async byte stream, returning the written size.
+            pub async fn $write<W: $write_trait + Unpin>(&self, w: W) -> Result<usize, Error> {
+                write_multihash($wrapper(w), self.code(), self.digest()).await
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        impl MultihashBuf {
+            /// Reads a multihash from an async byte stream.
+            ///
+            /// Digests larger than [`MultihashBuf::DEFAULT_MAX_SIZE`] are rejected.
+            pub async fn $read<R: $read_trait + Unpin>(r: R) -> Result<Self, Error> {
+                read_multihash_buf($wrapper(r), Self::DEFAULT_MAX_SIZE).await
+            }
+
+            /// Writes a multihash to an async byte stream, returning the written size.
+            pub async fn $write<W: $write_trait + Unpin>(&self, w: W) -> Result<usize, Error> {
+                write_multihash($wrapper(w), self.code(), self.digest()).await
+            }
+        }
+    };
+}
+
+#[cfg(feature = "futures-io")]
+impl_async_io!(
+    FuturesIo,
+    futures_io::AsyncRead,
+    futures_io::AsyncWrite,
+    read_futures_io,
+    write_futures_io
+);
+
+#[cfg(feature = "tokio")]
+impl_async_io!(
+    TokioIo,
+    tokio::io::AsyncRead,
+    tokio::io::AsyncWrite,
+    read_tokio,
+    write_tokio
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::executor::block_on;
+
+    #[test]
+    #[cfg(feature = "futures-io")]
+    fn test_futures_io() {
+        let mh = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let mut bytes = Vec::new();
+        let written = block_on(mh.write_futures_io(&mut bytes)).unwrap();
+        assert_eq!(written, mh.encoded_len());
+        assert_eq!(bytes, mh.to_bytes());
+
+        let decoded = block_on(Multihash::<32>::read_futures_io(&bytes[..])).unwrap();
+        assert_eq!(decoded, mh);
+        assert!(block_on(Multihash::<8>::read_futures_io(&bytes[..])).is_err());
+        assert!(block_on(Multihash::<32>::read_futures_io(&bytes[..10])).is_err());
+
+        let mut ref_bytes = Vec::new();
+        block_on(MultihashRef::from(&mh).write_futures_io(&mut ref_bytes)).unwrap();
+        assert_eq!(ref_bytes, bytes);
+
+        let buf = MultihashBuf::wrap(0x00, [7; 300]);
+        let mut buf_bytes = Vec::new();
+        block_on(buf.write_futures_io(&mut buf_bytes)).unwrap();
+        assert_eq!(buf_bytes, buf.to_bytes());
+        assert_eq!(
+            block_on(MultihashBuf::read_futures_io(&buf_bytes[..])).unwrap(),
+            buf
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn test_tokio() {
+        let mh = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let mut bytes = Vec::new();
+        let written = block_on(mh.write_tokio(&mut bytes)).unwrap();
+        assert_eq!(written, mh.encoded_len());
+        assert_eq!(bytes, mh.to_bytes());
+
+        let decoded = block_on(Multihash::<32>::read_tokio(&bytes[..])).unwrap();
+        assert_eq!(decoded, mh);
+        assert!(block_on(Multihash::<8>::read_tokio(&bytes[..])).is_err());
+        assert!(block_on(Multihash::<32>::read_tokio(&bytes[..10])).is_err());
+
+        let buf = MultihashBuf::wrap(0x00, [7; 300]);
+        let mut buf_bytes = Vec::new();
+        block_on(buf.write_tokio(&mut buf_bytes)).unwrap();
+        assert_eq!(
+            block_on(MultihashBuf::read_tokio(&buf_bytes[..])).unwrap(),
+            buf
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn test_tokio_spawn() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let mh = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let decoded = runtime.block_on(async move {
+            let bytes = tokio::spawn(async move {
+                let mut bytes = Vec::new();
+                mh.write_tokio(&mut bytes).await.map(|_| bytes)
+            })
+            .await
+            .unwrap()
+            .unwrap();
+            tokio::spawn(Multihash::<32>::read_tokio(io::Cursor::new(bytes)))
+                .await
+                .unwrap()
+        });
+        assert_eq!(decoded.unwrap(), mh);
+    }
+
+    fn assert_send<T: Send>(_: T) {}
+
+    // The futures are `Send` for any stream that is `Send`, also in generic code.
+    #[cfg(feature = "futures-io")]
+    #[allow(dead_code)]
+    fn assert_send_futures_io<R, W>(r: &mut R, w: &mut W)
+    where
+        R: futures_io::AsyncRead + Send + Unpin,
+        W: futures_io::AsyncWrite + Send + Unpin,
+    {
+        let mh = Multihash::<32>::default();
+        assert_send(Multihash::<32>::read_futures_io(&mut *r));
+        assert_send(MultihashBuf::read_futures_io(&mut *r));
+        assert_send(mh.write_futures_io(&mut *w));
+        assert_send(MultihashRef::from(&mh).write_futures_io(&mut *w));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[allow(dead_code)]
+    fn assert_send_tokio<R, W>(r: &mut R, w: &mut W)
+    where
+        R: tokio::io::AsyncRead + Send + Unpin,
+        W: tokio::io::AsyncWrite + Send + Unpin,
+    {
+        let mh = Multihash::<32>::default();
+        assert_send(Multihash::<32>::read_tokio(&mut *r));
+        assert_send(MultihashBuf::read_tokio(&mut *r));
+        assert_send(mh.write_tokio(&mut *w));
+        assert_send(MultihashRef::from(&mh).write_tokio(&mut *w));
+    }
+
+    #[test]
+    #[cfg(any(feature = "futures-io", feature = "tokio"))]
+    fn test_non_minimal_varint() {
+        let data = [0x80, 0x00, 0x00];
+        #[cfg(feature = "futures-io")]
+        assert!(block_on(Multihash::<32>::read_futures_io(&data[..])).is_err());
+        #[cfg(feature = "tokio")]
+        assert!(block_on(Multihash::<32>::read_tokio(&data[..])).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+//! Multihash bincode encoding
+//!
+//! Multihashes are encoded as their wire bytes without a length prefix, the varints and the digest
+//! are written as is. This makes the encoding independent of the bincode configuration.
+
+use bincode::de::read::{BorrowReader, Reader};
+use bincode::de::{BorrowDecode, BorrowDecoder, Decode, Decoder};
+use bincode::enc::write::Writer;
+use bincode::enc::{Encode, Encoder};
+use bincode::error::{DecodeError, EncodeError};
+use unsigned_varint::encode as varint_encode;
+
+use crate::multihash::read_multihash_header;
+use crate::{Multihash, MultihashRef};
+
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_io as io;
+
+impl<const S: usize> Encode for Multihash<S> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        write_multihash(encoder.writer(), self.code(), self.digest())
+    }
+}
+
+impl<Context, const S: usize> Decode<Context> for Multihash<S> {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        let mut reader = ReaderAdapter::new(decoder.reader());
+        let result = Multihash::read(&mut reader);
+        reader.finish(result)
+    }
+}
+
+impl<'de, Context, const S: usize> BorrowDecode<'de, Context> for Multihash<S> {
+    fn borrow_decode<D: BorrowDecoder<'de, Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, DecodeError> {
+        Self::decode(decoder)
+    }
+}
+
+impl Encode for MultihashRef<'_> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        write_multihash(encoder.writer(), self.code(), self.digest())
+    }
+}
+
+impl<'de, Context> BorrowDecode<'de, Context> for MultihashRef<'de> {
+    fn borrow_decode<D: BorrowDecoder<'de, Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, DecodeError> {
+        let mut reader = ReaderAdapter::new(decoder.borrow_reader());
+        let result = read_multihash_header(&mut reader, u8::MAX as usize);
+        let (code, size, _header_len) = reader.finish(result)?;
+        let digest = decoder.borrow_reader().take_bytes(size)?;
+        Ok(Self::wrap(code, digest).expect("the digest size was checked when reading the header"))
+    }
+}
+
+fn write_multihash<W: Writer>(w: &mut W, code: u64, digest: &[u8]) -> Result<(), EncodeError> {
+    let mut code_buf = varint_encode::u64_buffer();
+    let code = varint_encode::u64(code, &mut code_buf);
+
+    let mut size_buf = varint_encode::u64_buffer();
+    let size = varint_encode::u64(digest.len() as u64, &mut size_buf);
+
+    w.write(code)?;
+    w.write(size)?;
+    w.write(digest)
+}
+
+/// Reads from a bincode [`Reader`], keeping its error so that it can be returned unchanged.
+struct ReaderAdapter<'a, R> {
+    reader: &'a mut R,
+    error: Option<DecodeError>,
+}
+
+impl<'a, R: Reader> ReaderAdapter<'a, R> {
+    fn new(reader: &'a mut R) -> Self {
+        Self {
+            reader,
+            error: None,
+        }
+    }
+
+    /// Returns the error of the reader if there was one, the multihash error otherwise.
+    fn finish<T>(self, result: Result<T, crate::Error>) -> Result<T, DecodeError> {
+        match (result, self.error) {
+            (Ok(value), _) => Ok(value),
+            (Err(_), Some(err)) => Err(err),
+            (Err(err), None) => Err(err.into()),
+        }
+    }
+}
+
+impl<R: Reader> io::Read for ReaderAdapter<'_, R> {
+    fn read(&mut self, out: &mut [u8]) -> Result<usize, io::Error> {
+        // Bincode readers only support reading exact lengths, multihashes are read byte by byte
+        // or with the exact digest size.
+        match self.reader.read(out) {
+            Ok(()) => Ok(out.len()),
+            Err(err) => {
+                self.error = Some(err);
+                Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "failed to read from bincode reader",
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    use bincode::config;
+
+    #[test]
+    fn test_bincode() {
+        let mh = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let expected = mh.to_bytes();
+
+        // The encoding is the same for all configurations.
+        let standard = bincode::encode_to_vec(mh, config::standard()).unwrap();
+        let legacy = bincode::encode_to_vec(mh, config::legacy()).unwrap();
+        assert_eq!(standard, expected);
+        assert_eq!(legacy, expected);
+
+        let (decoded, len): (Multihash<32>, _) =
+            bincode::decode_from_slice(&expected, config::legacy()).unwrap();
+        assert_eq!(decoded, mh);
+        assert_eq!(len, expected.len());
+
+        let mh_ref = MultihashRef::from(&mh);
+        assert_eq!(
+            bincode::encode_to_vec(mh_ref, config::standard()).unwrap(),
+            expected
+        );
+        let (decoded, _): (MultihashRef, _) =
+            bincode::borrow_decode_from_slice(&expected, config::standard()).unwrap();
+        assert_eq!(decoded, mh_ref);
+        assert_eq!(decoded.digest().as_ptr(), expected[2..].as_ptr());
+
+        // Multihashes are self-delimiting within other values.
+        let pair = bincode::encode_to_vec((mh, 7u8), config::standard()).unwrap();
+        let ((decoded, rest), _): ((Multihash<32>, u8), _) =
+            bincode::decode_from_slice(&pair, config::standard()).unwrap();
+        assert_eq!(decoded, mh);
+        assert_eq!(rest, 7);
+    }
+
+    #[test]
+    fn test_bincode_errors() {
+        let mh = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let bytes = mh.to_bytes();
+
+        let err =
+            bincode::decode_from_slice::<Multihash<8>, _>(&bytes, config::standard()).unwrap_err();
+        assert!(matches!(err, DecodeError::OtherString(message)
+            if message == "Invalid multihash size 11 at byte offset 1."));
+
+        let err = bincode::decode_from_slice::<Multihash<32>, _>(&bytes[..5], config::standard())
+            .unwrap_err();
+        assert!(matches!(err, DecodeError::UnexpectedEnd { .. }));
+        let err =
+            bincode::borrow_decode_from_slice::<MultihashRef, _>(&bytes[..5], config::standard())
+                .unwrap_err();
+        assert!(matches!(err, DecodeError::UnexpectedEnd { .. }));
+    }
+}
@@ -0,0 +1,257 @@
+use unsigned_varint::decode;
+
+use crate::multihash::varint_len;
+use crate::{Error, Multihash};
+
+/// The progress reported by [`MultihashDecoder::feed`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DecoderStatus<const S: usize> {
+    /// All given bytes were consumed, at least this many more bytes are needed to finish decoding.
+    NeedMore(usize),
+    /// A multihash was decoded. The second value is the number of bytes consumed from the slice
+    /// that was passed in last, the remaining bytes weren't looked at.
+    Done(Multihash<S>, usize),
+}
+
+/// A push-based, resumable multihash decoder.
+///
+/// Contrary to [`Multihash::read`], which needs the whole multihash to be available in a blocking
+/// reader, this decoder accepts the input in arbitrary chunks. The code varint, the size varint
+/// and the digest may all be split across chunk boundaries.
+///
+/// The same validation as in [`Multihash::from_bytes`] is applied, varints need to be minimally
+/// encoded and the digest needs to fit into `S` bytes. After a multihash was decoded or an error
+/// occurred, the decoder is reset, so it can be used to decode the next multihash.
+///
+/// # Example
+///
+/// ```
+/// use multihash::{DecoderStatus, Multihash, MultihashDecoder};
+///
+/// let bytes = [0x16, 0x03, 0x01, 0x02, 0x03, 0xff];
+/// let mut decoder = MultihashDecoder::<32>::new();
+/// assert_eq!(decoder.feed(&bytes[..1]).unwrap(), DecoderStatus::NeedMore(1));
+/// assert_eq!(decoder.feed(&bytes[1..3]).unwrap(), DecoderStatus::NeedMore(2));
+/// let expected = Multihash::wrap(0x16, &[0x01, 0x02, 0x03]).unwrap();
+/// assert_eq!(
+///     decoder.feed(&bytes[3..]).unwrap(),
+///     DecoderStatus::Done(expected, 2)
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultihashDecoder<const S: usize> {
+    state: State,
+    digest: [u8; S],
+}
+
+#[derive(Clone, Copy, Debug)]
+enum State {
+    /// Reading the code varint.
+    Code(Varint),
+    /// Reading the size varint.
+    Size { code: u64, varint: Varint },
+    /// Reading the digest.
+    Digest {
+        code: u64,
+        size: usize,
+        filled: usize,
+    },
+}
+
+/// The bytes of a partially read varint.
+#[derive(Clone, Copy, Debug)]
+struct Varint {
+    /// A varint encoded `u64` is at most 10 bytes long.
+    buf: [u8; 10],
+    len: usize,
+}
+
+impl Varint {
+    const fn new() -> Self {
+        Self {
+            buf: [0; 10],
+            len: 0,
+        }
+    }
+
+    /// Adds a byte, returns the decoded value once the varint is complete.
+    fn push(&mut self, byte: u8) -> Result<Option<u64>, Error> {
+        self.buf[self.len] = byte;
+        self.len += 1;
+        // If the buffer is full without reaching the last byte, decoding reports the overflow.
+        if decode::is_last(byte) || self.len == self.buf.len() {
+            decode::u64(&self.buf[..self.len])
+                .map(|(value, _)| Some(value))
+                .map_err(crate::error::unsigned_varint_decode_to_multihash_error)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<const S: usize> Default for MultihashDecoder<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const S: usize> MultihashDecoder<S> {
+    /// Creates a new decoder.
+    pub const fn new() -> Self {
+        Self {
+            state: State::Code(Varint::new()),
+            digest: [0; S],
+        }
+    }
+
+    /// Discards any partially decoded multihash.
+    pub fn reset(&mut self) {
+        self.state = State::Code(Varint::new());
+    }
+
+    /// Feeds the next chunk of bytes into the decoder.
+    ///
+    /// Returns [`DecoderStatus::Done`] as soon as a full multihash was decoded, any bytes after it
+    /// are not consumed. Otherwise the whole input is consumed and [`DecoderStatus::NeedMore`] is
+    /// returned.
+    pub fn feed(&mut self, input: &[u8]) -> Result<DecoderStatus<S>, Error> {
+        let result = self.feed_inner(input);
+        if !matches!(result, Ok(DecoderStatus::NeedMore(_))) {
+            self.reset();
+        }
+        result
+    }
+
+    fn feed_inner(&mut self, input: &[u8]) -> Result<DecoderStatus<S>, Error> {
+        let mut consumed = 0;
+        loop {
+            match &mut self.state {
+                State::Code(varint) => {
+                    let Some(&byte) = input.get(consumed) else {
+                        return Ok(DecoderStatus::NeedMore(1));
+                    };
+                    consumed += 1;
+                    if let Some(code) = varint.push(byte).map_err(|err| err.at(0))? {
+                        self.state = State::Size {
+                            code,
+                            varint: Varint::new(),
+                        };
+                    }
+                }
+                State::Size { code, varint } => {
+                    let Some(&byte) = input.get(consumed) else {
+                        return Ok(DecoderStatus::NeedMore(1));
+                    };
+                    consumed += 1;
+                    let offset = varint_len(*code);
+                    if let Some(size) = varint.push(byte).map_err(|err| err.at(offset))? {
+                        let max_size = S.min(u8::MAX as usize);
+                        if size > max_size as u64 {
+                            return Err(Error::digest_too_large(size, max_size).at(offset));
+                        }
+                        self.state = State::Digest {
+                            code: *code,
+                            size: size as usize,
+                            filled: 0,
+                        };
+                    }
+                }
+                State::Digest { code, size, filled } => {
+                    let available = &input[consumed..];
+                    let len = available.len().min(*size - *filled);
+                    self.digest[*filled..*filled + len].copy_from_slice(&available[..len]);
+                    *filled += len;
+                    consumed += len;
+                    if filled < size {
+                        return Ok(DecoderStatus::NeedMore(*size - *filled));
+                    }
+                    let multihash = Multihash::wrap(*code, &self.digest[..*size])?;
+                    return Ok(DecoderStatus::Done(multihash, consumed));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    const BYTES: [u8; 13] = [
+        0x80, 0x01, 0x0a, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09,
+    ];
+
+    #[test]
+    fn test_byte_by_byte() {
+        let expected = Multihash::<32>::from_bytes(&BYTES).unwrap();
+        let mut decoder = MultihashDecoder::<32>::new();
+        let (last, init) = BYTES.split_last().unwrap();
+        for byte in init {
+            assert!(matches!(
+                decoder.feed(&[*byte]).unwrap(),
+                DecoderStatus::NeedMore(_)
+            ));
+        }
+        assert_eq!(
+            decoder.feed(&[*last]).unwrap(),
+            DecoderStatus::Done(expected, 1)
+        );
+    }
+
+    #[test]
+    fn test_chunks() {
+        let expected = Multihash::<32>::from_bytes(&BYTES).unwrap();
+        let mut decoder = MultihashDecoder::<32>::new();
+        assert_eq!(decoder.feed(&[]).unwrap(), DecoderStatus::NeedMore(1));
+        assert_eq!(
+            decoder.feed(&BYTES[..1]).unwrap(),
+            DecoderStatus::NeedMore(1)
+        );
+        assert_eq!(
+            decoder.feed(&BYTES[1..5]).unwrap(),
+            DecoderStatus::NeedMore(8)
+        );
+
+        // Bytes after the multihash aren't consumed.
+        let mut rest = BYTES[5..].to_vec();
+        rest.extend_from_slice(&BYTES);
+        assert_eq!(
+            decoder.feed(&rest).unwrap(),
+            DecoderStatus::Done(expected, 8)
+        );
+
+        // The decoder was reset and decodes the next multihash.
+        assert_eq!(
+            decoder.feed(&rest[8..]).unwrap(),
+            DecoderStatus::Done(expected, BYTES.len())
+        );
+    }
+
+    #[test]
+    fn test_empty_digest() {
+        let mut decoder = MultihashDecoder::<32>::new();
+        assert_eq!(
+            decoder.feed(&[0x12, 0x00, 0xff]).unwrap(),
+            DecoderStatus::Done(Multihash::wrap(0x12, &[]).unwrap(), 2)
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut decoder = MultihashDecoder::<8>::new();
+        // Digest too large.
+        let err = decoder.feed(&BYTES[..3]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::DigestTooLarge { size: 10, max: 8 });
+        assert_eq!(err.offset(), Some(2));
+        // Non-minimal varint.
+        assert!(decoder.feed(&[0x80, 0x00]).is_err());
+        // Varint overflow.
+        assert!(decoder.feed(&[0xff; 10]).is_err());
+        // The decoder is usable after an error.
+        assert!(matches!(
+            decoder.feed(&[0x00, 0x01, 0x01]).unwrap(),
+            DecoderStatus::Done(_, 3)
+        ));
+    }
+}
@@ -3,94 +3,309 @@ use crate::no_std_io as io;
 #[cfg(feature = "std")]
 use std::io;
 
+use core::fmt;
+
 use unsigned_varint::decode;
 
-/// Opaque error struct for operations involving a [`Multihash`](crate::Multihash).
+/// Error struct for operations involving a [`Multihash`](crate::Multihash).
+///
+/// Use [`Error::kind`] to find out what went wrong and [`Error::offset`] to find out where.
 #[derive(Debug)]
 pub struct Error {
-    kind: Kind,
+    kind: ErrorKind,
+    offset: Option<usize>,
+    source: Option<Source>,
+}
+
+/// The kind of an [`Error`].
+///
+/// This list is intended to grow over time and it is not recommended to exhaustively match
+/// against it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// An I/O error occurred, see [`core::error::Error::source`] for the underlying error.
+    Io,
+    /// The input ended before the multihash was complete.
+    Truncated,
+    /// A varint is not minimally encoded.
+    NonMinimalVarint,
+    /// A varint doesn't fit into a `u64`.
+    VarintOverflow,
+    /// A varint is invalid for another reason.
+    InvalidVarint,
+    /// The digest is larger than the allocated (or maximum supported) size.
+    DigestTooLarge {
+        /// The size of the digest.
+        size: u64,
+        /// The maximum size of the digest.
+        max: usize,
+    },
+    /// The digest size doesn't match the size the hash function of the code produces.
+    DigestSizeMismatch {
+        /// The size of the digest.
+        size: u64,
+        /// The minimum expected size.
+        min: usize,
+        /// The maximum expected size.
+        max: usize,
+    },
+    /// There were bytes left after the digest.
+    TrailingBytes {
+        /// The number of bytes after the digest.
+        len: usize,
+    },
+    /// The string is not a valid multibase encoding.
+    InvalidMultibase,
+    /// The code is not supported by a code table.
+    UnsupportedCode {
+        /// The unsupported code.
+        code: u64,
+    },
+}
+
+/// The underlying error of an [`Error`].
+#[derive(Debug)]
+enum Source {
+    Io(io::Error),
+    // Only exposed through `source()`, which requires the `std` feature.
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    Varint(decode::Error),
+    #[cfg(feature = "multibase")]
+    Multibase(multibase::Error),
 }
 
 impl Error {
-    pub(crate) const fn invalid_size(size: u64) -> Self {
+    /// Returns the kind of this error.
+    pub const fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the byte offset (from the start of the multihash) of the part that failed to decode.
+    ///
+    /// That's the start of the code varint, the size varint, the digest or the trailing bytes.
+    /// Errors that didn't occur while decoding don't have an offset.
+    pub const fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    pub(crate) const fn digest_too_large(size: u64, max: usize) -> Self {
         Self {
-            kind: Kind::InvalidSize(size),
+            kind: ErrorKind::DigestTooLarge { size, max },
+            offset: None,
+            source: None,
         }
     }
 
-    #[cfg(not(feature = "std"))]
-    pub(crate) const fn insufficient_varint_bytes() -> Self {
+    pub(crate) const fn trailing_bytes(len: usize) -> Self {
         Self {
-            kind: Kind::Varint(decode::Error::Insufficient),
+            kind: ErrorKind::TrailingBytes { len },
+            offset: None,
+            source: None,
         }
     }
 
+    /// The input ended before the whole digest could be read.
+    ///
+    /// This is the same error that reading the digest from a truncated byte stream returns.
+    pub(crate) fn insufficient_digest_bytes() -> Self {
+        io_to_multihash_error(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "failed to fill whole buffer",
+        ))
+    }
+
+    #[cfg(not(feature = "std"))]
+    pub(crate) fn insufficient_varint_bytes() -> Self {
+        unsigned_varint_decode_to_multihash_error(decode::Error::Insufficient)
+    }
+
     #[cfg(not(feature = "std"))]
-    pub(crate) const fn varint_overflow() -> Self {
+    pub(crate) fn varint_overflow() -> Self {
+        unsigned_varint_decode_to_multihash_error(decode::Error::Overflow)
+    }
+
+    /// Sets the byte offset at which decoding failed.
+    pub(crate) fn at(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
         Self {
-            kind: Kind::Varint(decode::Error::Overflow),
+            kind,
+            offset: None,
+            source: None,
         }
     }
 }
 
-impl core::fmt::Display for Error {
-    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
-        self.kind.fmt(f)
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.kind, &self.source) {
+            (ErrorKind::Io, Some(Source::Io(err))) => write!(f, "{err}")?,
+            #[cfg(feature = "multibase")]
+            (ErrorKind::InvalidMultibase, Some(Source::Multibase(err))) => {
+                write!(f, "Invalid multibase string: {err}")?
+            }
+            (kind, _) => write!(f, "{kind}")?,
+        }
+        if let Some(offset) = self.offset {
+            write!(f, " at byte offset {offset}")?;
+        }
+        f.write_str(".")
     }
 }
 
-#[derive(Debug)]
-enum Kind {
-    /// Io error.
-    Io(io::Error),
-    /// Invalid multihash size.
-    InvalidSize(u64),
-    /// Invalid varint.
-    Varint(decode::Error),
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io => f.write_str("I/O error"),
+            Self::Truncated => f.write_str("Unexpected end of input"),
+            Self::NonMinimalVarint => f.write_str("Varint is not minimally encoded"),
+            Self::VarintOverflow => f.write_str("Varint does not fit into 64 bits"),
+            Self::InvalidVarint => f.write_str("Invalid varint"),
+            Self::DigestTooLarge { size, .. } => write!(f, "Invalid multihash size {size}"),
+            Self::DigestSizeMismatch { size, min, max } if min == max => {
+                write!(
+                    f,
+                    "Digest size {size} does not match the expected size {max}"
+                )
+            }
+            Self::DigestSizeMismatch { size, min, max } => {
+                write!(
+                    f,
+                    "Digest size {size} is not within the expected range {min}..={max}"
+                )
+            }
+            Self::TrailingBytes { len } => write!(f, "Found {len} bytes after the digest"),
+            Self::InvalidMultibase => f.write_str("Invalid multibase string"),
+            Self::UnsupportedCode { code } => write!(f, "Unsupported multihash code {code}"),
+        }
+    }
 }
 
 #[cfg(feature = "std")]
 pub(crate) fn unsigned_varint_to_multihash_error(err: unsigned_varint::io::ReadError) -> Error {
     match err {
         unsigned_varint::io::ReadError::Io(err) => io_to_multihash_error(err),
-        unsigned_varint::io::ReadError::Decode(err) => Error {
-            kind: Kind::Varint(err),
-        },
+        unsigned_varint::io::ReadError::Decode(err) => {
+            unsigned_varint_decode_to_multihash_error(err)
+        }
         other => io_to_multihash_error(io::Error::other(other)),
     }
 }
 
-#[cfg(not(feature = "std"))]
 pub(crate) fn unsigned_varint_decode_to_multihash_error(
     err: unsigned_varint::decode::Error,
 ) -> Error {
+    let kind = match err {
+        decode::Error::Insufficient => ErrorKind::Truncated,
+        decode::Error::Overflow => ErrorKind::VarintOverflow,
+        decode::Error::NotMinimal => ErrorKind::NonMinimalVarint,
+        _ => ErrorKind::InvalidVarint,
+    };
     Error {
-        kind: Kind::Varint(err),
+        kind,
+        offset: None,
+        source: Some(Source::Varint(err)),
+    }
+}
+
+#[cfg(feature = "multibase")]
+pub(crate) fn multibase_to_multihash_error(err: multibase::Error) -> Error {
+    Error {
+        kind: ErrorKind::InvalidMultibase,
+        offset: None,
+        source: Some(Source::Multibase(err)),
     }
 }
 
 pub(crate) fn io_to_multihash_error(err: io::Error) -> Error {
+    let kind = if err.kind() == io::ErrorKind::UnexpectedEof {
+        ErrorKind::Truncated
+    } else {
+        ErrorKind::Io
+    };
     Error {
-        kind: Kind::Io(err),
+        kind,
+        offset: None,
+        source: Some(Source::Io(err)),
     }
 }
 
-impl core::fmt::Display for Kind {
-    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+impl ErrorKind {
+    /// Returns a description without any details, for errors that only support static strings.
+    #[cfg(any(
+        feature = "scale-codec",
+        all(not(feature = "alloc"), any(feature = "minicbor", feature = "bincode"))
+    ))]
+    pub(crate) const fn static_description(&self) -> &'static str {
         match self {
-            Self::Io(err) => write!(f, "{err}"),
-            Self::InvalidSize(size) => write!(f, "Invalid multihash size {size}."),
-            Self::Varint(err) => write!(f, "{err}"),
+            Self::Io => "I/O error",
+            Self::Truncated => "Unexpected end of input",
+            Self::NonMinimalVarint => "Varint is not minimally encoded",
+            Self::VarintOverflow => "Varint does not fit into 64 bits",
+            Self::InvalidVarint => "Invalid varint",
+            Self::DigestTooLarge { .. } => "Invalid multihash size",
+            Self::DigestSizeMismatch { .. } => "Digest size does not match",
+            Self::TrailingBytes { .. } => "Found bytes after the digest",
+            Self::InvalidMultibase => "Invalid multibase string",
+            Self::UnsupportedCode { .. } => "Unsupported multihash code",
         }
     }
 }
 
+/// Converts the error into a SCALE error, which only supports static descriptions.
+#[cfg(feature = "scale-codec")]
+impl From<Error> for parity_scale_codec::Error {
+    fn from(err: Error) -> Self {
+        parity_scale_codec::Error::from(err.kind.static_description())
+    }
+}
+
+/// Converts the error into a bincode decode error.
+///
+/// Without the `alloc` feature only a static description is kept.
+#[cfg(feature = "bincode")]
+impl From<Error> for bincode::error::DecodeError {
+    fn from(err: Error) -> Self {
+        #[cfg(feature = "alloc")]
+        return bincode::error::DecodeError::OtherString(alloc::string::ToString::to_string(&err));
+        #[cfg(not(feature = "alloc"))]
+        return bincode::error::DecodeError::Other(err.kind.static_description());
+    }
+}
+
+/// Converts the error into a minicbor decode error.
+///
+/// Without the `alloc` feature only a static description is kept.
+#[cfg(feature = "minicbor")]
+impl From<Error> for minicbor::decode::Error {
+    fn from(err: Error) -> Self {
+        #[cfg(feature = "alloc")]
+        return minicbor::decode::Error::message(err);
+        #[cfg(not(feature = "alloc"))]
+        return minicbor::decode::Error::message(err.kind.static_description());
+    }
+}
+
 impl core::error::Error for Error {
     fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
-        match &self.kind {
-            Kind::Io(inner) => Some(inner),
-            Kind::InvalidSize(_) => None,
-            Kind::Varint(_) => None, // FIXME: Does not implement `no_std_io2::Error`.
+        match &self.source {
+            Some(Source::Io(inner)) => Some(inner),
+            #[cfg(feature = "std")]
+            Some(Source::Varint(inner)) => Some(inner),
+            // Only implements `Error` with the `std` feature.
+            #[cfg(not(feature = "std"))]
+            Some(Source::Varint(_)) => None,
+            #[cfg(all(feature = "multibase", feature = "std"))]
+            Some(Source::Multibase(inner)) => Some(inner),
+            #[cfg(all(feature = "multibase", not(feature = "std")))]
+            Some(Source::Multibase(_)) => None,
+            None => None,
         }
     }
 }
@@ -11,11 +11,37 @@
 //! The `arb` feature flag enables the quickcheck arbitrary implementation for property based
 //! testing.
 //!
-//! For serializing the multihash there is support for [Serde] via the `serde-codec` feature and
-//! the [SCALE Codec] via the `scale-codec` feature.
+//! For serializing the multihash there is support for [Serde] via the `serde-codec` feature,
+//! the [SCALE Codec] via the `scale-codec` feature, [Borsh] via the `borsh` feature, CBOR via
+//! the `minicbor` feature and [bincode] 2 via the `bincode` feature.
+//! There's no `scale-info` `TypeInfo` implementation, as the SCALE encoding of the digest has no
+//! length prefix and can't be described with it.
+//!
+//! The `prost` feature provides a [Protobuf] message type for multihashes.
+//!
+//! The `multibase` feature enables encoding to and parsing from [multibase] strings. It's enabled
+//! by the `serde` feature, which uses a base58btc multibase string in human-readable formats. The
+//! `multihash::serde` module has adapters for other representations, e.g. hex.
+//!
+//! Multihashes can be read from and written to async byte streams with the `futures-io` and
+//! `tokio` features.
+//!
+//! The `bytes` feature adds support for encoding into and decoding from [`bytes`] buffers,
+//! including the zero-copy `MultihashBytes` type.
+//!
+//! The `rkyv` feature allows accessing archived multihashes with [rkyv] without deserializing them.
+//!
+//! The `subtle` feature implements [`ConstantTimeEq`] for `Multihash`.
 //!
 //! [Serde]: https://serde.rs
+//! [`bytes`]: https://docs.rs/bytes
+//! [`ConstantTimeEq`]: https://docs.rs/subtle/latest/subtle/trait.ConstantTimeEq.html
 //! [SCALE Codec]: https://github.com/paritytech/parity-scale-codec
+//! [Borsh]: https://borsh.io
+//! [bincode]: https://docs.rs/bincode
+//! [rkyv]: https://rkyv.org
+//! [Protobuf]: https://protobuf.dev
+//! [multibase]: https://github.com/multiformats/multibase
 //! [`multihash-derive`]: https://docs.rs/multihash-derive
 //! [`multihash-codetable`]: https://docs.rs/multihash-codetable
 
@@ -27,19 +53,43 @@ extern crate alloc;
 
 #[cfg(feature = "arb")]
 mod arb;
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+mod async_io;
+#[cfg(feature = "bincode")]
+mod bincode;
+mod decoder;
 mod error;
 mod multihash;
+#[cfg(feature = "alloc")]
+mod multihash_buf;
+#[cfg(feature = "bytes")]
+mod multihash_bytes;
+mod multihash_ref;
 #[cfg(not(feature = "std"))]
 pub mod no_std_io; // Make it public for downstream crates(e.g. `cid`).
+#[cfg(feature = "prost")]
+pub mod prost;
+#[cfg(feature = "rkyv")]
+mod rkyv;
 #[cfg(feature = "serde")]
-mod serde;
+pub mod serde;
 
 /// Multihash result.
 #[deprecated(note = "Use `Result<T, multihash::Error>` instead")]
 pub type Result<T> = core::result::Result<T, Error>;
 
-pub use crate::error::Error;
+pub use crate::decoder::{DecoderStatus, MultihashDecoder};
+pub use crate::error::{Error, ErrorKind};
 pub use crate::multihash::Multihash;
+#[cfg(feature = "alloc")]
+pub use crate::multihash_buf::MultihashBuf;
+#[cfg(feature = "bytes")]
+pub use crate::multihash_bytes::MultihashBytes;
+pub use crate::multihash_ref::MultihashRef;
+#[cfg(feature = "rkyv")]
+pub use crate::rkyv::ArchivedMultihash;
+#[cfg(feature = "multibase")]
+pub use multibase;
 
 /// Deprecated type-alias for the [`Multihash`] type.
 #[deprecated(since = "0.18.0", note = "Use `multihash::Multihash instead.")]
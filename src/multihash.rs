@@ -1,8 +1,9 @@
 use crate::Error;
+#[cfg(feature = "multibase")]
+use alloc::string::String;
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
-use core::convert::TryInto;
 use core::fmt::Debug;
 
 use unsigned_varint::encode as varint_encode;
@@ -57,8 +58,14 @@ impl<const S: usize> Default for Multihash<S> {
 impl<const S: usize> Multihash<S> {
     /// Wraps the digest in a multihash.
     pub const fn wrap(code: u64, input_digest: &[u8]) -> Result<Self, Error> {
-        if input_digest.len() > S {
-            return Err(Error::invalid_size(input_digest.len() as _));
+        // The size is encoded in a single byte, so it's also bounded by `u8::MAX`.
+        let max = if S < u8::MAX as usize {
+            S
+        } else {
+            u8::MAX as usize
+        };
+        if input_digest.len() > max {
+            return Err(Error::digest_too_large(input_digest.len() as _, max));
         }
         let size = input_digest.len();
         let mut digest = [0; S];
@@ -106,31 +113,95 @@ impl<const S: usize> Multihash<S> {
     where
         Self: Sized,
     {
+        let len = bytes.len();
         let result = Self::read(&mut bytes)?;
         // There were more bytes supplied than read
         if !bytes.is_empty() {
-            return Err(Error::invalid_size(bytes.len().try_into().expect(
-                "Currently the maximum size is 255, therefore always fits into usize",
-            )));
+            return Err(Error::trailing_bytes(bytes.len()).at(len - bytes.len()));
         }
 
         Ok(result)
     }
 
+    /// Parses a multihash from bytes in a const context.
+    ///
+    /// It applies the same validation as [`Multihash::from_bytes`], but panics on malformed input,
+    /// which results in a compile-time error when used to initialize a `const` or `static`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multihash::Multihash;
+    ///
+    /// const EMPTY_SHA2_256: Multihash<32> = Multihash::from_bytes_const(&[
+    ///     0x12, 0x20, 0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8, 0x99,
+    ///     0x6f, 0xb9, 0x24, 0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c, 0xa4, 0x95, 0x99, 0x1b,
+    ///     0x78, 0x52, 0xb8, 0x55,
+    /// ]);
+    /// assert_eq!(EMPTY_SHA2_256.code(), 0x12);
+    /// ```
+    pub const fn from_bytes_const(bytes: &[u8]) -> Self {
+        let (code, pos) = decode_varint_const(bytes, 0);
+        let (size, pos) = decode_varint_const(bytes, pos);
+        if size > S as u64 || size > u8::MAX as u64 {
+            panic!("multihash digest is too large");
+        }
+        let size = size as usize;
+        if bytes.len() < pos + size {
+            panic!("unexpected end of multihash input");
+        }
+        if bytes.len() > pos + size {
+            panic!("found bytes after the multihash digest");
+        }
+        let mut digest = [0; S];
+        let mut i = 0;
+        while i < size {
+            digest[i] = bytes[pos + i];
+            i += 1;
+        }
+        Self {
+            code,
+            size: size as u8,
+            digest,
+        }
+    }
+
+    /// Encodes the multihash into an array in a const context.
+    ///
+    /// `N` needs to be exactly [`Multihash::encoded_len`], otherwise this function panics.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multihash::Multihash;
+    ///
+    /// const MH: Multihash<32> = Multihash::from_bytes_const(&[0x12, 0x02, 0xab, 0xcd]);
+    /// const BYTES: [u8; MH.encoded_len()] = MH.encode_to_array();
+    /// assert_eq!(BYTES, [0x12, 0x02, 0xab, 0xcd]);
+    /// ```
+    pub const fn encode_to_array<const N: usize>(&self) -> [u8; N] {
+        if N != self.encoded_len() {
+            panic!("array length must match the encoded length of the multihash");
+        }
+        let (mut bytes, pos) = encode_varint_const(self.code, [0; N], 0);
+        let (bytes_with_size, pos) = encode_varint_const(self.size as u64, bytes, pos);
+        bytes = bytes_with_size;
+        let mut i = 0;
+        while i < self.size as usize {
+            bytes[pos + i] = self.digest[i];
+            i += 1;
+        }
+        bytes
+    }
+
     /// Writes a multihash to a byte stream, returning the written size.
     pub fn write<W: io::Write>(&self, w: W) -> Result<usize, Error> {
-        write_multihash(w, self.code(), self.size(), self.digest())
+        write_multihash(w, self.code(), self.digest())
     }
 
     /// Returns the length in bytes needed to encode this multihash into bytes.
-    pub fn encoded_len(&self) -> usize {
-        let mut code_buf = varint_encode::u64_buffer();
-        let code = varint_encode::u64(self.code, &mut code_buf);
-
-        let mut size_buf = varint_encode::u8_buffer();
-        let size = varint_encode::u8(self.size, &mut size_buf);
-
-        code.len() + size.len() + usize::from(self.size)
+    pub const fn encoded_len(&self) -> usize {
+        encoded_len(self.code, self.size as usize)
     }
 
     #[cfg(feature = "alloc")]
@@ -144,6 +215,15 @@ impl<const S: usize> Multihash<S> {
         bytes
     }
 
+    #[cfg(feature = "multibase")]
+    /// Returns the multihash as a string in the given multibase.
+    ///
+    /// The string starts with the multibase prefix character of the chosen base, hence it can be
+    /// parsed again with [`str::parse`] without knowing the base.
+    pub fn to_string_base(&self, base: multibase::Base) -> String {
+        multibase::encode(base, self.to_bytes())
+    }
+
     /// Truncates the multihash to the given size. It's up to the caller to ensure that the new size
     /// is secure (cryptographically) to use.
     ///
@@ -160,7 +240,7 @@ impl<const S: usize> Multihash<S> {
     pub fn resize<const R: usize>(&self) -> Result<Multihash<R>, Error> {
         let size = self.size as usize;
         if size > R {
-            return Err(Error::invalid_size(self.size as u64));
+            return Err(Error::digest_too_large(self.size as u64, R));
         }
         let mut mh = Multihash {
             code: self.code,
@@ -171,6 +251,29 @@ impl<const S: usize> Multihash<S> {
         Ok(mh)
     }
 
+    /// Compares two multihashes in constant time.
+    ///
+    /// Contrary to `==`, the time this takes doesn't depend on the content of the digests, only on
+    /// their size. Use it when comparing against secret or attacker-controlled digests.
+    ///
+    /// Like the `subtle` crate, this is a best-effort protection against compiler optimizations,
+    /// every byte goes through [`core::hint::black_box`] so that the comparison can't return
+    /// early. With the `subtle` feature, [`subtle::ConstantTimeEq`] is implemented as well.
+    ///
+    /// [`subtle::ConstantTimeEq`]: https://docs.rs/subtle/latest/subtle/trait.ConstantTimeEq.html
+    pub fn eq_constant_time<const R: usize>(&self, other: &Multihash<R>) -> bool {
+        // The code and the size are not considered secret.
+        if self.code != other.code || self.size != other.size {
+            return false;
+        }
+        let diff = self
+            .digest()
+            .iter()
+            .zip(other.digest())
+            .fold(0, |acc, (a, b)| acc | core::hint::black_box(a ^ b));
+        core::hint::black_box(diff) == 0
+    }
+
     /// Decomposes struct, useful when needing a `Sized` array or moving all the data into another type
     ///
     /// It is recommended to use `digest()` `code()` and `size()` for most cases.
@@ -196,6 +299,21 @@ impl<const S: usize> From<Multihash<S>> for Vec<u8> {
     }
 }
 
+/// Parses a multibase encoded multihash, e.g. one returned by [`Multihash::to_string_base`].
+///
+/// Any base supported by the [`multibase`] crate is accepted, the base is determined by the
+/// prefix character of the string.
+#[cfg(feature = "multibase")]
+impl<const S: usize> core::str::FromStr for Multihash<S> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (_base, bytes) =
+            multibase::decode(s).map_err(crate::error::multibase_to_multihash_error)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
 impl<const A: usize, const B: usize> PartialEq<Multihash<B>> for Multihash<A> {
     fn eq(&self, other: &Multihash<B>) -> bool {
         // NOTE: there's no need to explicitly check the sizes, that's implicit in the digest.
@@ -203,6 +321,14 @@ impl<const A: usize, const B: usize> PartialEq<Multihash<B>> for Multihash<A> {
     }
 }
 
+#[cfg(feature = "subtle")]
+impl<const S: usize> subtle::ConstantTimeEq for Multihash<S> {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        // Slices of different lengths compare as unequal without looking at the content.
+        self.code.ct_eq(&other.code) & self.digest().ct_eq(other.digest())
+    }
+}
+
 #[cfg(feature = "scale-codec")]
 impl<const S: usize> parity_scale_codec::Encode for Multihash<S> {
     fn encode_to<EncOut: parity_scale_codec::Output + ?Sized>(&self, dest: &mut EncOut) {
@@ -231,7 +357,8 @@ impl<const S: usize> parity_scale_codec::Decode for Multihash<S> {
             digest: [0; S],
         };
         if mh.size as usize > S {
-            return Err(parity_scale_codec::Error::from("invalid size"));
+            // The size follows the 8 bytes of the code.
+            return Err(Error::digest_too_large(mh.size as u64, S).at(8).into());
         }
         // For a valid multihash, the length of digest must equal to the size.
         input.read(&mut mh.digest[..mh.size as usize])?;
@@ -239,16 +366,106 @@ impl<const S: usize> parity_scale_codec::Decode for Multihash<S> {
     }
 }
 
+/// The digest is encoded without a length prefix, so the maximum length is the code, the size and
+/// the allocated size of the digest.
+#[cfg(feature = "scale-codec")]
+impl<const S: usize> parity_scale_codec::MaxEncodedLen for Multihash<S> {
+    fn max_encoded_len() -> usize {
+        8 + 1 + S
+    }
+}
+
+/// The Borsh encoding is the code as little-endian `u64`, the size as `u8` and then the digest,
+/// without any padding.
+#[cfg(feature = "borsh")]
+impl<const S: usize> borsh::BorshSerialize for Multihash<S> {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        self.code.serialize(writer)?;
+        self.size.serialize(writer)?;
+        // The digest is written without a length prefix, its length is already given by `size`.
+        writer.write_all(self.digest())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl<const S: usize> borsh::BorshDeserialize for Multihash<S> {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let mut mh = Multihash {
+            code: borsh::BorshDeserialize::deserialize_reader(reader)?,
+            size: borsh::BorshDeserialize::deserialize_reader(reader)?,
+            digest: [0; S],
+        };
+        if mh.size as usize > S {
+            use alloc::string::ToString;
+
+            // The size follows the 8 bytes of the code.
+            let err = Error::digest_too_large(mh.size as u64, S).at(8);
+            return Err(borsh::io::Error::new(
+                borsh::io::ErrorKind::InvalidData,
+                err.to_string(),
+            ));
+        }
+        reader.read_exact(&mut mh.digest[..mh.size as usize])?;
+        Ok(mh)
+    }
+}
+
+/// The CBOR encoding is a byte string containing the bytes of the multihash.
+#[cfg(feature = "minicbor")]
+impl<C, const S: usize> minicbor::Encode<C> for Multihash<S> {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        let mut code_buf = varint_encode::u64_buffer();
+        let code = varint_encode::u64(self.code, &mut code_buf);
+
+        let mut size_buf = varint_encode::u64_buffer();
+        let size = varint_encode::u64(self.size.into(), &mut size_buf);
+
+        // Write the parts directly, so that no buffer for the whole multihash is needed.
+        e.bytes_len(self.encoded_len() as u64)?;
+        for part in [code, size, self.digest()] {
+            e.writer_mut()
+                .write_all(part)
+                .map_err(minicbor::encode::Error::write)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "minicbor")]
+impl<C, const S: usize> minicbor::CborLen<C> for Multihash<S> {
+    fn cbor_len(&self, ctx: &mut C) -> usize {
+        // The head of a byte string has the same length as the head of an integer.
+        let len = self.encoded_len();
+        len.cbor_len(ctx) + len
+    }
+}
+
+#[cfg(feature = "minicbor")]
+impl<'b, C, const S: usize> minicbor::Decode<'b, C> for Multihash<S> {
+    fn decode(
+        d: &mut minicbor::Decoder<'b>,
+        _ctx: &mut C,
+    ) -> Result<Self, minicbor::decode::Error> {
+        let position = d.position();
+        let bytes = d.bytes()?;
+        Self::from_bytes(bytes).map_err(|err| minicbor::decode::Error::from(err).at(position))
+    }
+}
+
 /// Writes the multihash to a byte stream.
-fn write_multihash<W>(mut w: W, code: u64, size: u8, digest: &[u8]) -> Result<usize, Error>
+pub(crate) fn write_multihash<W>(mut w: W, code: u64, digest: &[u8]) -> Result<usize, Error>
 where
     W: io::Write,
 {
     let mut code_buf = varint_encode::u64_buffer();
     let code = varint_encode::u64(code, &mut code_buf);
 
-    let mut size_buf = varint_encode::u8_buffer();
-    let size = varint_encode::u8(size, &mut size_buf);
+    let mut size_buf = varint_encode::u64_buffer();
+    let size = varint_encode::u64(digest.len() as u64, &mut size_buf);
 
     let written = code.len() + size.len() + digest.len();
 
@@ -262,6 +479,63 @@ where
     Ok(written)
 }
 
+/// Returns the length in bytes needed to encode a multihash with the given code and digest size.
+pub(crate) const fn encoded_len(code: u64, size: usize) -> usize {
+    varint_len(code) + varint_len(size as u64) + size
+}
+
+/// Returns the length in bytes of the varint encoding of the given value.
+pub(crate) const fn varint_len(mut value: u64) -> usize {
+    let mut len = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        len += 1;
+    }
+    len
+}
+
+/// Decodes a varint starting at `pos`, returns the value and the position after it.
+///
+/// Panics if the varint is truncated, doesn't fit into a `u64` or isn't minimally encoded.
+const fn decode_varint_const(bytes: &[u8], mut pos: usize) -> (u64, usize) {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        if pos >= bytes.len() {
+            panic!("unexpected end of multihash input");
+        }
+        let byte = bytes[pos];
+        pos += 1;
+        // The tenth byte may only contain the highest bit of the value.
+        if shift == 63 && byte > 1 {
+            panic!("varint does not fit into 64 bits");
+        }
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            if byte == 0 && shift > 0 {
+                panic!("varint is not minimally encoded");
+            }
+            return (value, pos);
+        }
+        shift += 7;
+    }
+}
+
+/// Encodes a varint into `bytes` starting at `pos`, returns the bytes and the position after it.
+const fn encode_varint_const<const N: usize>(
+    mut value: u64,
+    mut bytes: [u8; N],
+    mut pos: usize,
+) -> ([u8; N], usize) {
+    while value >= 0x80 {
+        bytes[pos] = (value as u8) | 0x80;
+        value >>= 7;
+        pos += 1;
+    }
+    bytes[pos] = value as u8;
+    (bytes, pos + 1)
+}
+
 /// Reads a multihash from a byte stream that contains a full multihash (code, size and the digest)
 ///
 /// Returns the code, size and the digest. The size is the actual size and not the
@@ -272,19 +546,34 @@ fn read_multihash<R, const S: usize>(mut r: R) -> Result<(u64, u8, [u8; S]), Err
 where
     R: io::Read,
 {
-    let code = read_u64(&mut r)?;
-    let size = read_u64(&mut r)?;
-
-    if size > S as u64 || size > u8::MAX as u64 {
-        return Err(Error::invalid_size(size));
-    }
+    let (code, size, header_len) = read_multihash_header(&mut r, S.min(u8::MAX as usize))?;
 
     let mut digest = [0; S];
-    r.read_exact(&mut digest[..size as usize])
-        .map_err(crate::error::io_to_multihash_error)?;
+    r.read_exact(&mut digest[..size])
+        .map_err(|err| crate::error::io_to_multihash_error(err).at(header_len))?;
     Ok((code, size as u8, digest))
 }
 
+/// Reads the code and the size of a multihash from a byte stream, leaving the digest unread.
+///
+/// Returns the code, the size and the number of bytes read. Sizes larger than `max_size` are
+/// rejected.
+pub(crate) fn read_multihash_header<R>(
+    mut r: R,
+    max_size: usize,
+) -> Result<(u64, usize, usize), Error>
+where
+    R: io::Read,
+{
+    let code = read_u64(&mut r).map_err(|err| err.at(0))?;
+    let code_len = varint_len(code);
+    let size = read_u64(&mut r).map_err(|err| err.at(code_len))?;
+    if size > max_size as u64 {
+        return Err(Error::digest_too_large(size, max_size).at(code_len));
+    }
+    Ok((code, size as usize, code_len + varint_len(size)))
+}
+
 #[cfg(feature = "std")]
 pub(crate) fn read_u64<R: io::Read>(r: R) -> Result<u64, Error> {
     unsigned_varint::io::read_u64(r).map_err(crate::error::unsigned_varint_to_multihash_error)
@@ -316,6 +605,58 @@ pub(crate) fn read_u64<R: io::Read>(mut r: R) -> Result<u64, Error> {
 mod tests {
     use super::*;
 
+    #[test]
+    #[cfg(all(feature = "borsh", feature = "alloc"))]
+    fn test_borsh() {
+        use alloc::{string::ToString, vec};
+
+        let mh1 = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let mh1_bytes = borsh::to_vec(&mh1).unwrap();
+        let mut expected = vec![0x12, 0, 0, 0, 0, 0, 0, 0, 11];
+        expected.extend_from_slice(b"hello world");
+        assert_eq!(mh1_bytes, expected);
+        let mh2: Multihash<32> = borsh::from_slice(&mh1_bytes).unwrap();
+        assert_eq!(mh1, mh2);
+
+        // The allocated size doesn't influence the encoding.
+        let mh3 = Multihash::<64>::wrap(0x12, b"hello world").unwrap();
+        assert_eq!(borsh::to_vec(&mh3).unwrap(), mh1_bytes);
+
+        let err = borsh::from_slice::<Multihash<8>>(&mh1_bytes).unwrap_err();
+        assert_eq!(err.kind(), borsh::io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "Invalid multihash size 11 at byte offset 8."
+        );
+        // Truncated digest.
+        assert!(borsh::from_slice::<Multihash<32>>(&mh1_bytes[..12]).is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "minicbor", feature = "alloc"))]
+    fn test_minicbor() {
+        use alloc::string::ToString;
+
+        let mh = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let mut bytes = [0; 64];
+        minicbor::encode(mh, &mut bytes[..]).unwrap();
+        let len = minicbor::len(mh);
+        assert_eq!(len, 1 + 13);
+        // A byte string of length 13 followed by the multihash bytes.
+        assert_eq!(bytes[0], 0x4d);
+        assert_eq!(&bytes[1..len], &mh.to_bytes()[..]);
+        let decoded: Multihash<32> = minicbor::decode(&bytes[..len]).unwrap();
+        assert_eq!(decoded, mh);
+
+        // The same validation as `from_bytes()` is applied.
+        let err = minicbor::decode::<Multihash<8>>(&bytes[..len]).unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("Invalid multihash size 11 at byte offset 1."));
+        bytes[0] = 0x4c;
+        assert!(minicbor::decode::<Multihash<32>>(&bytes[..len - 1]).is_err());
+    }
+
     #[test]
     #[cfg(feature = "scale-codec")]
     fn test_scale() {
@@ -338,6 +679,64 @@ mod tests {
         assert_eq!(mh1_bytes, mh3_bytes);
     }
 
+    #[test]
+    #[cfg(feature = "scale-codec")]
+    fn test_scale_max_encoded_len() {
+        use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+
+        assert_eq!(Multihash::<32>::max_encoded_len(), 8 + 1 + 32);
+
+        // A digest that fills the allocated size has the maximum length.
+        let mh = Multihash::<4>::wrap(0x12, &[1, 2, 3, 4]).unwrap();
+        let bytes = mh.encode();
+        assert_eq!(bytes.len(), Multihash::<4>::max_encoded_len());
+        assert_eq!(Multihash::<4>::decode(&mut &bytes[..]).unwrap(), mh);
+
+        let truncated = mh.truncate(2);
+        assert!(truncated.encode().len() < Multihash::<4>::max_encoded_len());
+    }
+
+    #[test]
+    #[cfg(feature = "multibase")]
+    fn test_multibase() {
+        use multibase::Base;
+
+        let mh = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let bases = [
+            Base::Base58Btc,
+            Base::Base32Lower,
+            Base::Base32Upper,
+            Base::Base36Lower,
+            Base::Base64,
+            Base::Base64Url,
+            Base::Base16Lower,
+        ];
+        for base in bases {
+            let encoded = mh.to_string_base(base);
+            assert_eq!(encoded.chars().next(), Some(base.code()));
+            let decoded: Multihash<32> = encoded.parse().unwrap();
+            assert_eq!(mh, decoded);
+        }
+
+        assert_eq!(
+            mh.to_string_base(Base::Base16Lower),
+            "f120b68656c6c6f20776f726c64"
+        );
+
+        // Trailing bytes are rejected.
+        assert!("f120b68656c6c6f20776f726c6400"
+            .parse::<Multihash<32>>()
+            .is_err());
+        // Digests that don't fit are rejected.
+        assert!("f120b68656c6c6f20776f726c64"
+            .parse::<Multihash<8>>()
+            .is_err());
+        // Unknown multibase prefix.
+        assert!("#120b68656c6c6f20776f726c64"
+            .parse::<Multihash<32>>()
+            .is_err());
+    }
+
     #[test]
     fn test_eq_sizes() {
         let mh1 = Multihash::<32>::default();
@@ -345,6 +744,143 @@ mod tests {
         assert_eq!(mh1, mh2);
     }
 
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_const() {
+        const BYTES: [u8; 13] = [
+            0x80, 0x01, 0x0a, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09,
+        ];
+        const MH: Multihash<32> = Multihash::from_bytes_const(&BYTES);
+        const ENCODED: [u8; MH.encoded_len()] = MH.encode_to_array();
+        assert_eq!(MH, Multihash::<32>::from_bytes(&BYTES).unwrap());
+        assert_eq!(ENCODED, BYTES);
+
+        for code in [0, 0x7f, 0x80, 0x3fff, 0x4000, u64::MAX] {
+            let mh = Multihash::<32>::wrap(code, b"hello world").unwrap();
+            assert_eq!(mh.encoded_len(), mh.to_bytes().len());
+        }
+        let mh = Multihash::<32>::wrap(u64::MAX, b"hello world").unwrap();
+        let bytes = mh.encode_to_array::<22>();
+        assert_eq!(bytes[..], mh.to_bytes());
+        assert_eq!(Multihash::<32>::from_bytes_const(&bytes), mh);
+    }
+
+    #[test]
+    #[should_panic(expected = "not minimally encoded")]
+    fn test_from_bytes_const_non_minimal() {
+        Multihash::<32>::from_bytes_const(&[0x80, 0x00, 0x00]);
+    }
+
+    #[test]
+    #[should_panic(expected = "bytes after the multihash digest")]
+    fn test_from_bytes_const_trailing_bytes() {
+        Multihash::<32>::from_bytes_const(&[0x12, 0x01, 0xff, 0xff]);
+    }
+
+    #[test]
+    #[should_panic(expected = "too large")]
+    fn test_from_bytes_const_too_large() {
+        Multihash::<2>::from_bytes_const(&[0x12, 0x03, 0xff, 0xff, 0xff]);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit into 64 bits")]
+    fn test_from_bytes_const_overflow() {
+        Multihash::<32>::from_bytes_const(&[0xff; 11]);
+    }
+
+    #[test]
+    fn test_eq_constant_time() {
+        let mh1 = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let mh2 = Multihash::<64>::wrap(0x12, b"hello world").unwrap();
+        assert!(mh1.eq_constant_time(&mh2));
+        assert!(!mh1.eq_constant_time(&Multihash::<32>::wrap(0x13, b"hello world").unwrap()));
+        assert!(!mh1.eq_constant_time(&Multihash::<32>::wrap(0x12, b"hello worle").unwrap()));
+        assert!(!mh1.eq_constant_time(&mh1.truncate(5)));
+    }
+
+    #[test]
+    #[cfg(feature = "subtle")]
+    fn test_subtle() {
+        use subtle::ConstantTimeEq;
+
+        let mh1 = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let mh2 = Multihash::<32>::wrap(0x12, b"hello worle").unwrap();
+        assert!(bool::from(ConstantTimeEq::ct_eq(&mh1, &mh1)));
+        assert!(!bool::from(ConstantTimeEq::ct_eq(&mh1, &mh2)));
+        assert!(!bool::from(ConstantTimeEq::ct_eq(&mh1, &mh1.truncate(5))));
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        use crate::ErrorKind;
+        #[cfg(feature = "alloc")]
+        use alloc::string::ToString;
+
+        let err = Multihash::<32>::from_bytes(&[]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Truncated);
+        assert_eq!(err.offset(), Some(0));
+
+        // Truncated digest.
+        let err = Multihash::<32>::from_bytes(&[0x12, 0x20, 0xff]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Truncated);
+        assert_eq!(err.offset(), Some(2));
+
+        // Trailing bytes.
+        let err = Multihash::<32>::from_bytes(&[0x00, 0x03, 1, 2, 3, 4, 5]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TrailingBytes { len: 2 });
+        assert_eq!(err.offset(), Some(5));
+        #[cfg(feature = "alloc")]
+        assert_eq!(
+            err.to_string(),
+            "Found 2 bytes after the digest at byte offset 5."
+        );
+
+        // Digest larger than the allocated size.
+        let err = Multihash::<2>::from_bytes(&[0x80, 0x01, 0x03, 1, 2, 3]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::DigestTooLarge { size: 3, max: 2 });
+        assert_eq!(err.offset(), Some(2));
+
+        // Non-minimal size varint.
+        let err = Multihash::<32>::from_bytes(&[0x12, 0x80, 0x00]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NonMinimalVarint);
+        assert_eq!(err.offset(), Some(1));
+
+        // Code doesn't fit into a `u64`.
+        let err = Multihash::<32>::from_bytes(&[0xff; 11]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::VarintOverflow);
+        assert_eq!(err.offset(), Some(0));
+
+        let err = Multihash::<2>::wrap(0, b"foo").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::DigestTooLarge { size: 3, max: 2 });
+        assert_eq!(err.offset(), None);
+        #[cfg(feature = "alloc")]
+        assert_eq!(err.to_string(), "Invalid multihash size 3.");
+    }
+
+    #[test]
+    fn test_wrap_digest_larger_than_u8() {
+        // The size is a single byte, larger digests can't be represented.
+        let err = Multihash::<512>::wrap(0, &[1; 300]).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            crate::ErrorKind::DigestTooLarge {
+                size: 300,
+                max: 255
+            }
+        );
+        assert!(Multihash::<512>::wrap(0, &[1; 255]).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_error_source() {
+        use std::error::Error as _;
+
+        let err = Multihash::<32>::from_bytes(&[0x12, 0x80, 0x00]).unwrap_err();
+        assert!(err.source().is_some());
+    }
+
     #[test]
     fn decode_non_minimal_error() {
         // This is a non-minimal varint.
@@ -0,0 +1,275 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use core::convert::TryFrom;
+
+use crate::multihash::{encoded_len, read_multihash_header, write_multihash};
+use crate::{Error, Multihash, MultihashRef};
+
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_io as io;
+
+/// A heap allocated Multihash that supports digests of arbitrary size.
+///
+/// [`Multihash`] stores the digest in a fixed size buffer and its size in a `u8`, hence it cannot
+/// hold digests larger than 255 bytes. The multihash spec encodes the size as an unsigned varint
+/// though, which is e.g. needed for inlined data with the identity hash or for large outputs of
+/// extendable-output functions. `MultihashBuf` allocates the digest on the heap instead.
+///
+/// When parsing, the size of the digest is limited to [`MultihashBuf::DEFAULT_MAX_SIZE`] bytes,
+/// so that untrusted input cannot trigger huge allocations. Use
+/// [`MultihashBuf::read_with_max_size`] or [`MultihashBuf::from_bytes_with_max_size`] to
+/// configure a different limit.
+///
+/// # Example
+///
+/// ```
+/// use multihash::{Multihash, MultihashBuf};
+///
+/// const IDENTITY: u64 = 0x00;
+/// let data = [0xab; 300];
+/// let mh = MultihashBuf::wrap(IDENTITY, &data[..]);
+/// assert_eq!(mh.size(), 300);
+///
+/// let bytes = mh.to_bytes();
+/// assert_eq!(MultihashBuf::from_bytes(&bytes).unwrap(), mh);
+///
+/// // It doesn't fit into a fixed size multihash.
+/// assert!(Multihash::<64>::try_from(mh).is_err());
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MultihashBuf {
+    /// The code of the Multihash.
+    code: u64,
+    /// The digest, its length is the size of the Multihash.
+    digest: Box<[u8]>,
+}
+
+impl MultihashBuf {
+    /// The maximum digest size in bytes that is accepted by [`MultihashBuf::read`] and
+    /// [`MultihashBuf::from_bytes`].
+    pub const DEFAULT_MAX_SIZE: usize = 64 * 1024;
+
+    /// Wraps the digest in a multihash.
+    ///
+    /// A `Vec<u8>` or `Box<[u8]>` digest is moved into the multihash without copying.
+    pub fn wrap(code: u64, digest: impl Into<Box<[u8]>>) -> Self {
+        Self {
+            code,
+            digest: digest.into(),
+        }
+    }
+
+    /// Returns the code of the multihash.
+    pub const fn code(&self) -> u64 {
+        self.code
+    }
+
+    /// Returns the size of the digest.
+    pub const fn size(&self) -> usize {
+        self.digest.len()
+    }
+
+    /// Returns the digest.
+    pub fn digest(&self) -> &[u8] {
+        &self.digest
+    }
+
+    /// Reads a multihash from a byte stream.
+    ///
+    /// Digests larger than [`MultihashBuf::DEFAULT_MAX_SIZE`] are rejected.
+    pub fn read<R: io::Read>(r: R) -> Result<Self, Error> {
+        Self::read_with_max_size(r, Self::DEFAULT_MAX_SIZE)
+    }
+
+    /// Reads a multihash from a byte stream, rejecting digests larger than `max_size` bytes.
+    pub fn read_with_max_size<R: io::Read>(mut r: R, max_size: usize) -> Result<Self, Error> {
+        let (code, size, header_len) = read_multihash_header(&mut r, max_size)?;
+
+        let mut digest = vec![0; size];
+        r.read_exact(&mut digest)
+            .map_err(|err| crate::error::io_to_multihash_error(err).at(header_len))?;
+        Ok(Self::wrap(code, digest))
+    }
+
+    /// Parses a multihash from bytes.
+    ///
+    /// You need to make sure the passed in bytes have the correct length. The digest length
+    /// needs to match the `size` value of the multihash. Digests larger than
+    /// [`MultihashBuf::DEFAULT_MAX_SIZE`] are rejected.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Self::from_bytes_with_max_size(bytes, Self::DEFAULT_MAX_SIZE)
+    }
+
+    /// Parses a multihash from bytes, rejecting digests larger than `max_size` bytes.
+    pub fn from_bytes_with_max_size(mut bytes: &[u8], max_size: usize) -> Result<Self, Error> {
+        let len = bytes.len();
+        let result = Self::read_with_max_size(&mut bytes, max_size)?;
+        // There were more bytes supplied than read
+        if !bytes.is_empty() {
+            return Err(Error::trailing_bytes(bytes.len()).at(len - bytes.len()));
+        }
+
+        Ok(result)
+    }
+
+    /// Writes a multihash to a byte stream, returning the written size.
+    pub fn write<W: io::Write>(&self, w: W) -> Result<usize, Error> {
+        write_multihash(w, self.code, &self.digest)
+    }
+
+    /// Returns the length in bytes needed to encode this multihash into bytes.
+    pub fn encoded_len(&self) -> usize {
+        encoded_len(self.code, self.digest.len())
+    }
+
+    /// Returns the bytes of a multihash.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.encoded_len());
+        let written = self
+            .write(&mut bytes)
+            .expect("writing to a vec should never fail");
+        debug_assert_eq!(written, bytes.len());
+        bytes
+    }
+
+    /// Decomposes struct, useful when needing to move the digest into another type.
+    pub fn into_inner(self) -> (u64, Box<[u8]>) {
+        let Self { code, digest } = self;
+        (code, digest)
+    }
+}
+
+impl<const S: usize> From<Multihash<S>> for MultihashBuf {
+    fn from(multihash: Multihash<S>) -> Self {
+        Self::wrap(multihash.code(), multihash.digest())
+    }
+}
+
+impl From<MultihashRef<'_>> for MultihashBuf {
+    fn from(multihash: MultihashRef<'_>) -> Self {
+        Self::wrap(multihash.code(), multihash.digest())
+    }
+}
+
+/// Fails if the digest is larger than the allocated size of the [`Multihash`].
+impl<const S: usize> TryFrom<MultihashBuf> for Multihash<S> {
+    type Error = Error;
+
+    fn try_from(multihash: MultihashBuf) -> Result<Self, Self::Error> {
+        Multihash::wrap(multihash.code, &multihash.digest)
+    }
+}
+
+impl From<MultihashBuf> for Vec<u8> {
+    fn from(multihash: MultihashBuf) -> Self {
+        multihash.to_bytes()
+    }
+}
+
+impl<const S: usize> PartialEq<Multihash<S>> for MultihashBuf {
+    fn eq(&self, other: &Multihash<S>) -> bool {
+        self.code == other.code() && self.digest() == other.digest()
+    }
+}
+
+impl<const S: usize> PartialEq<MultihashBuf> for Multihash<S> {
+    fn eq(&self, other: &MultihashBuf) -> bool {
+        other == self
+    }
+}
+
+/// The SCALE encoding is the code followed by the digest as byte vector (compact encoded length
+/// and the raw bytes).
+///
+/// **NOTE** This differs from the encoding of [`Multihash`], which stores the size as `u8`.
+#[cfg(feature = "scale-codec")]
+impl parity_scale_codec::Encode for MultihashBuf {
+    fn encode_to<EncOut: parity_scale_codec::Output + ?Sized>(&self, dest: &mut EncOut) {
+        self.code.encode_to(dest);
+        self.digest.encode_to(dest);
+    }
+}
+
+#[cfg(feature = "scale-codec")]
+impl parity_scale_codec::EncodeLike for MultihashBuf {}
+
+#[cfg(feature = "scale-codec")]
+impl parity_scale_codec::Decode for MultihashBuf {
+    fn decode<DecIn: parity_scale_codec::Input>(
+        input: &mut DecIn,
+    ) -> Result<Self, parity_scale_codec::Error> {
+        let code = parity_scale_codec::Decode::decode(input)?;
+        let size = <parity_scale_codec::Compact<u64>>::decode(input)?.0;
+        if size > Self::DEFAULT_MAX_SIZE as u64 {
+            // The size follows the 8 bytes of the code.
+            return Err(Error::digest_too_large(size, Self::DEFAULT_MAX_SIZE)
+                .at(8)
+                .into());
+        }
+        let mut digest = vec![0; size as usize];
+        input.read(&mut digest)?;
+        Ok(Self::wrap(code, digest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_large_digest_roundtrip() {
+        let mh = MultihashBuf::wrap(0x00, vec![7; 1000]);
+        let bytes = mh.to_bytes();
+        // Code (1 byte), size varint (2 bytes) and the digest.
+        assert_eq!(bytes.len(), 1003);
+        assert_eq!(mh.encoded_len(), bytes.len());
+        assert_eq!(MultihashBuf::from_bytes(&bytes).unwrap(), mh);
+        assert!(MultihashBuf::from_bytes_with_max_size(&bytes, 999).is_err());
+        assert!(MultihashBuf::from_bytes(&bytes[..1002]).is_err());
+        // Fixed size multihashes reject it.
+        assert!(Multihash::<64>::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_multihash_conversion() {
+        let mh = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let buf = MultihashBuf::from(mh);
+        assert_eq!(buf, mh);
+        assert_eq!(mh, buf);
+        assert_eq!(buf.to_bytes(), mh.to_bytes());
+        assert_eq!(MultihashBuf::from(MultihashRef::from(&mh)), buf);
+
+        let roundtrip = Multihash::<32>::try_from(buf.clone()).unwrap();
+        assert_eq!(roundtrip, mh);
+        assert!(Multihash::<8>::try_from(buf).is_err());
+    }
+
+    #[test]
+    fn test_multihash_conversion_too_large() {
+        let buf = MultihashBuf::wrap(0x00, vec![7; 300]);
+        let err = Multihash::<512>::try_from(buf).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            crate::ErrorKind::DigestTooLarge {
+                size: 300,
+                max: 255
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "scale-codec")]
+    fn test_scale() {
+        use parity_scale_codec::{Decode, Encode};
+
+        let mh = MultihashBuf::wrap(0x00, vec![7; 300]);
+        let bytes = mh.encode();
+        let decoded = MultihashBuf::decode(&mut &bytes[..]).unwrap();
+        assert_eq!(mh, decoded);
+    }
+}
@@ -0,0 +1,282 @@
+use bytes::{Buf, BufMut, Bytes};
+use unsigned_varint::encode as varint_encode;
+
+use crate::multihash::read_multihash_header;
+use crate::{Error, ErrorKind, Multihash, MultihashRef};
+
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_io as io;
+
+/// A Multihash backed by a [`Bytes`] buffer.
+///
+/// Parsing it doesn't copy the digest, it only keeps a reference-counted handle to the encoded
+/// multihash. The encoded bytes can be handed out again via [`MultihashBytes::as_bytes`] without
+/// re-encoding.
+///
+/// # Example
+///
+/// ```
+/// use bytes::Bytes;
+/// use multihash::MultihashBytes;
+///
+/// let mut buf = Bytes::from_static(&[0x16, 0x03, 0x01, 0x02, 0x03, 0xff]);
+/// let mh = MultihashBytes::decode_from(&mut buf).unwrap();
+/// assert_eq!(mh.code(), 0x16);
+/// assert_eq!(mh.digest(), &[0x01, 0x02, 0x03]);
+/// assert_eq!(&buf[..], &[0xff]);
+/// ```
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MultihashBytes {
+    /// The code of the Multihash.
+    code: u64,
+    /// The length of the code and size varints.
+    header_len: usize,
+    /// The whole encoded multihash.
+    encoded: Bytes,
+}
+
+impl MultihashBytes {
+    /// Parses a multihash from bytes without copying the digest.
+    ///
+    /// The same rules as for [`Multihash::from_bytes`] apply, the digest length needs to match
+    /// the `size` value of the multihash.
+    pub fn from_bytes(bytes: Bytes) -> Result<Self, Error> {
+        let (code, size, header_len) = read_multihash_header(&mut &bytes[..], u8::MAX as usize)?;
+        if bytes.len() < header_len + size {
+            return Err(Error::insufficient_digest_bytes().at(header_len));
+        }
+        if bytes.len() > header_len + size {
+            return Err(
+                Error::trailing_bytes(bytes.len() - header_len - size).at(header_len + size)
+            );
+        }
+        Ok(Self {
+            code,
+            header_len,
+            encoded: bytes,
+        })
+    }
+
+    /// Splits a multihash off the front of the buffer without copying the digest.
+    ///
+    /// The buffer is advanced past the multihash, any bytes after it are left in place. On error
+    /// the buffer is not modified.
+    pub fn decode_from(buf: &mut Bytes) -> Result<Self, Error> {
+        let (code, size, header_len) = read_multihash_header(&mut &buf[..], u8::MAX as usize)?;
+        if buf.len() < header_len + size {
+            return Err(Error::insufficient_digest_bytes().at(header_len));
+        }
+        Ok(Self {
+            code,
+            header_len,
+            encoded: buf.split_to(header_len + size),
+        })
+    }
+
+    /// Returns the code of the multihash.
+    pub const fn code(&self) -> u64 {
+        self.code
+    }
+
+    /// Returns the size of the digest.
+    pub fn size(&self) -> u8 {
+        (self.encoded.len() - self.header_len) as u8
+    }
+
+    /// Returns the digest.
+    pub fn digest(&self) -> &[u8] {
+        &self.encoded[self.header_len..]
+    }
+
+    /// Returns the encoded multihash.
+    pub const fn as_bytes(&self) -> &Bytes {
+        &self.encoded
+    }
+
+    /// Returns the encoded multihash.
+    pub fn into_bytes(self) -> Bytes {
+        self.encoded
+    }
+
+    /// Copies the digest into a [`Multihash`] with the given allocated size.
+    ///
+    /// This function fails if the digest is larger than the target size.
+    pub fn to_owned<const S: usize>(&self) -> Result<Multihash<S>, Error> {
+        Multihash::wrap(self.code, self.digest())
+    }
+}
+
+impl<'a> From<&'a MultihashBytes> for MultihashRef<'a> {
+    fn from(multihash: &'a MultihashBytes) -> Self {
+        Self::wrap(multihash.code(), multihash.digest())
+            .expect("the digest size was checked when parsing")
+    }
+}
+
+impl<const S: usize> PartialEq<Multihash<S>> for MultihashBytes {
+    fn eq(&self, other: &Multihash<S>) -> bool {
+        self.code == other.code() && self.digest() == other.digest()
+    }
+}
+
+impl<const S: usize> PartialEq<MultihashBytes> for Multihash<S> {
+    fn eq(&self, other: &MultihashBytes) -> bool {
+        other == self
+    }
+}
+
+impl<const S: usize> Multihash<S> {
+    /// Writes the multihash into a buffer, returning the written size.
+    ///
+    /// Panics if the buffer doesn't have enough remaining capacity, see [`BufMut::put_slice`].
+    pub fn put_into<B: BufMut>(&self, buf: &mut B) -> usize {
+        put_multihash(buf, self.code(), self.digest())
+    }
+
+    /// Reads a multihash from the front of a buffer and advances it past the multihash.
+    ///
+    /// Like [`MultihashBytes::decode_from`], the buffer is not modified on error. The digest may
+    /// span several chunks of a non-contiguous buffer, the code and the size (at most 11 bytes)
+    /// are only checked before advancing if they are within the first chunk. Otherwise they are
+    /// read like with [`Multihash::read`] and the buffer may have been partially advanced on
+    /// error.
+    pub fn decode_from<B: Buf>(buf: &mut B) -> Result<Self, Error> {
+        let (code, size, header_len) =
+            match read_multihash_header(buf.chunk(), S.min(u8::MAX as usize)) {
+                Ok(header) => header,
+                // The header continues in the next chunk.
+                Err(err)
+                    if err.kind() == ErrorKind::Truncated
+                        && buf.chunk().len() < buf.remaining() =>
+                {
+                    return Self::read(BufReader(buf));
+                }
+                Err(err) => return Err(err),
+            };
+        if buf.remaining() < header_len + size {
+            return Err(Error::insufficient_digest_bytes().at(header_len));
+        }
+        buf.advance(header_len);
+        let mut digest = [0; S];
+        buf.copy_to_slice(&mut digest[..size]);
+        Self::wrap(code, &digest[..size])
+    }
+}
+
+impl MultihashRef<'_> {
+    /// Writes the multihash into a buffer, returning the written size.
+    ///
+    /// Panics if the buffer doesn't have enough remaining capacity, see [`BufMut::put_slice`].
+    pub fn put_into<B: BufMut>(&self, buf: &mut B) -> usize {
+        put_multihash(buf, self.code(), self.digest())
+    }
+}
+
+fn put_multihash<B: BufMut>(buf: &mut B, code: u64, digest: &[u8]) -> usize {
+    let mut code_buf = varint_encode::u64_buffer();
+    let code = varint_encode::u64(code, &mut code_buf);
+
+    let mut size_buf = varint_encode::u64_buffer();
+    let size = varint_encode::u64(digest.len() as u64, &mut size_buf);
+
+    buf.put_slice(code);
+    buf.put_slice(size);
+    buf.put_slice(digest);
+    code.len() + size.len() + digest.len()
+}
+
+/// Reads from a [`Buf`], advancing it by the number of bytes read.
+struct BufReader<'a, B>(&'a mut B);
+
+impl<B: Buf> io::Read for BufReader<'_, B> {
+    fn read(&mut self, out: &mut [u8]) -> Result<usize, io::Error> {
+        let len = out.len().min(self.0.remaining());
+        self.0.copy_to_slice(&mut out[..len]);
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use bytes::BytesMut;
+
+    #[test]
+    fn test_put_and_decode() {
+        let mh = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let mut buf = BytesMut::new();
+        let written = mh.put_into(&mut buf);
+        assert_eq!(written, mh.encoded_len());
+        assert_eq!(MultihashRef::from(&mh).put_into(&mut buf), written);
+        assert_eq!(&buf[..written], &mh.to_bytes()[..]);
+
+        // Decoding works across non-contiguous chunks.
+        let mut chained = Buf::chain(&buf[..5], &buf[5..]);
+        assert_eq!(Multihash::<32>::decode_from(&mut chained).unwrap(), mh);
+        assert_eq!(chained.remaining(), written);
+        assert_eq!(Multihash::<32>::decode_from(&mut chained).unwrap(), mh);
+        assert!(!chained.has_remaining());
+
+        let err = Multihash::<32>::decode_from(&mut chained).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Truncated);
+
+        // A failed decode leaves the buffer untouched, also if the digest spans several chunks.
+        let mut truncated = Buf::chain(&buf[..5], &buf[5..written - 1]);
+        let err = Multihash::<32>::decode_from(&mut truncated).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Truncated);
+        assert_eq!(err.offset(), Some(2));
+        assert_eq!(truncated.remaining(), written - 1);
+        let mut too_large = &buf[..];
+        let err = Multihash::<8>::decode_from(&mut too_large).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::DigestTooLarge { size: 11, max: 8 });
+        assert_eq!(too_large.len(), 2 * written);
+
+        // The header may span several chunks as well.
+        let mut split_header = Buf::chain(&buf[..1], &buf[1..written]);
+        assert_eq!(Multihash::<32>::decode_from(&mut split_header).unwrap(), mh);
+        assert!(!split_header.has_remaining());
+    }
+
+    #[test]
+    fn test_multihash_bytes() {
+        let mh = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let mut encoded = BytesMut::new();
+        mh.put_into(&mut encoded);
+        encoded.put_u8(0xff);
+        let mut buf = encoded.freeze();
+        let start = buf.as_ptr();
+
+        let mh_bytes = MultihashBytes::decode_from(&mut buf).unwrap();
+        assert_eq!(mh_bytes.code(), mh.code());
+        assert_eq!(mh_bytes.size(), mh.size());
+        assert_eq!(mh_bytes, mh);
+        assert_eq!(mh, mh_bytes);
+        assert_eq!(MultihashRef::from(&mh_bytes), mh);
+        assert_eq!(mh_bytes.to_owned::<32>().unwrap(), mh);
+        // The bytes are shared, not copied.
+        assert_eq!(mh_bytes.as_bytes().as_ptr(), start);
+        assert_eq!(&buf[..], &[0xff]);
+
+        let err = MultihashBytes::from_bytes(mh_bytes.as_bytes().slice(..5)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Truncated);
+        assert_eq!(err.offset(), Some(2));
+
+        // A failed decode leaves the buffer untouched.
+        let mut truncated = mh_bytes.as_bytes().slice(..5);
+        assert!(MultihashBytes::decode_from(&mut truncated).is_err());
+        assert_eq!(truncated.len(), 5);
+
+        let mut trailing = BytesMut::from(&mh_bytes.as_bytes()[..]);
+        trailing.put_u8(0xff);
+        let err = MultihashBytes::from_bytes(trailing.freeze()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TrailingBytes { len: 1 });
+        assert_eq!(
+            MultihashBytes::from_bytes(mh_bytes.clone().into_bytes()).unwrap(),
+            mh_bytes
+        );
+    }
+}
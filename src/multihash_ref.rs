@@ -0,0 +1,162 @@
+use crate::multihash::{encoded_len, read_multihash_header, write_multihash};
+use crate::{Error, Multihash};
+
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_io as io;
+
+/// A borrowed view of a Multihash.
+///
+/// Contrary to [`Multihash`] the digest isn't copied into a fixed size buffer, it points into the
+/// bytes the view was parsed from. This makes it cheap to inspect the code of a multihash or to
+/// compare digests without knowing the maximum digest size upfront.
+///
+/// # Example
+///
+/// ```
+/// use multihash::{Multihash, MultihashRef};
+///
+/// let bytes = [0x16, 0x03, 0x01, 0x02, 0x03];
+/// let mh_ref = MultihashRef::from_bytes(&bytes).unwrap();
+/// assert_eq!(mh_ref.code(), 0x16);
+/// assert_eq!(mh_ref.size(), 3);
+/// assert_eq!(mh_ref.digest(), &bytes[2..]);
+///
+/// let mh = mh_ref.to_owned::<32>().unwrap();
+/// assert_eq!(mh_ref, mh);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MultihashRef<'a> {
+    /// The code of the Multihash.
+    code: u64,
+    /// The digest, its length is the size of the Multihash.
+    digest: &'a [u8],
+}
+
+impl<'a> MultihashRef<'a> {
+    /// Wraps the digest in a multihash view.
+    ///
+    /// This function fails if the digest is larger than the maximum size of 255 bytes.
+    pub const fn wrap(code: u64, digest: &'a [u8]) -> Result<Self, Error> {
+        if digest.len() > u8::MAX as usize {
+            return Err(Error::digest_too_large(digest.len() as _, u8::MAX as usize));
+        }
+        Ok(Self { code, digest })
+    }
+
+    /// Returns the code of the multihash.
+    pub const fn code(&self) -> u64 {
+        self.code
+    }
+
+    /// Returns the size of the digest.
+    pub const fn size(&self) -> u8 {
+        self.digest.len() as u8
+    }
+
+    /// Returns the digest.
+    pub const fn digest(&self) -> &'a [u8] {
+        self.digest
+    }
+
+    /// Parses a multihash view from bytes.
+    ///
+    /// The same rules as for [`Multihash::from_bytes`] apply, the digest length needs to match
+    /// the `size` value of the multihash.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let mut rest = bytes;
+        let (code, size, header_len) = read_multihash_header(&mut rest, u8::MAX as usize)?;
+
+        if rest.len() < size {
+            return Err(Error::insufficient_digest_bytes().at(header_len));
+        }
+        let (digest, rest) = rest.split_at(size);
+        // There were more bytes supplied than read
+        if !rest.is_empty() {
+            return Err(Error::trailing_bytes(rest.len()).at(header_len + size));
+        }
+
+        Ok(Self { code, digest })
+    }
+
+    /// Copies the digest into a [`Multihash`] with the given allocated size.
+    ///
+    /// This function fails if the digest is larger than the target size.
+    pub const fn to_owned<const S: usize>(&self) -> Result<Multihash<S>, Error> {
+        Multihash::wrap(self.code, self.digest)
+    }
+
+    /// Writes a multihash to a byte stream, returning the written size.
+    pub fn write<W: io::Write>(&self, w: W) -> Result<usize, Error> {
+        write_multihash(w, self.code(), self.digest())
+    }
+
+    /// Returns the length in bytes needed to encode this multihash into bytes.
+    pub fn encoded_len(&self) -> usize {
+        encoded_len(self.code, self.digest.len())
+    }
+}
+
+impl<'a, const S: usize> From<&'a Multihash<S>> for MultihashRef<'a> {
+    fn from(multihash: &'a Multihash<S>) -> Self {
+        Self {
+            code: multihash.code(),
+            digest: multihash.digest(),
+        }
+    }
+}
+
+impl<const S: usize> PartialEq<Multihash<S>> for MultihashRef<'_> {
+    fn eq(&self, other: &Multihash<S>) -> bool {
+        self.code == other.code() && self.digest == other.digest()
+    }
+}
+
+impl<const S: usize> PartialEq<MultihashRef<'_>> for Multihash<S> {
+    fn eq(&self, other: &MultihashRef<'_>) -> bool {
+        other == self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_bytes() {
+        let mh = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let mut bytes = [0; 34];
+        let written = mh.write(&mut bytes[..]).unwrap();
+
+        let mh_ref = MultihashRef::from_bytes(&bytes[..written]).unwrap();
+        assert_eq!(mh_ref.code(), mh.code());
+        assert_eq!(mh_ref.size(), mh.size());
+        assert_eq!(mh_ref.digest(), mh.digest());
+        assert_eq!(mh_ref.encoded_len(), written);
+        assert_eq!(mh_ref, mh);
+        assert_eq!(mh, mh_ref);
+        assert_eq!(MultihashRef::from(&mh), mh_ref);
+        assert_eq!(mh_ref.to_owned::<32>().unwrap(), mh);
+        assert!(mh_ref.to_owned::<8>().is_err());
+
+        let mut rewritten = [0; 34];
+        assert_eq!(mh_ref.write(&mut rewritten[..]).unwrap(), written);
+        assert_eq!(rewritten, bytes);
+    }
+
+    #[test]
+    fn test_from_bytes_errors() {
+        // Empty input.
+        assert!(MultihashRef::from_bytes(&[]).is_err());
+        // Truncated digest.
+        assert!(MultihashRef::from_bytes(&[0x12, 0x20, 0xff]).is_err());
+        // Trailing bytes.
+        assert!(MultihashRef::from_bytes(&[0x00, 0x03, 1, 2, 3, 4]).is_err());
+        // Size doesn't fit into a `u8`.
+        assert!(MultihashRef::from_bytes(&[0x00, 0x80, 0x02]).is_err());
+        // Non-minimal varint.
+        assert!(MultihashRef::from_bytes(&[0x80, 0x00, 0x00]).is_err());
+    }
+}
@@ -20,7 +20,7 @@ impl core::error::Error for Error {}
 ///
 /// [`io::Error`]: Error
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub(super) enum ErrorKind {
+pub(crate) enum ErrorKind {
     /// An error returned when an operation could not be completed because a
     /// call to [`write`] returned [`Ok(0)`].
     ///
@@ -51,12 +51,12 @@ impl Error {
     /// This function is used to generically create I/O errors which do not
     /// originate from the OS itself. The `error` argument is an arbitrary
     /// payload which will be contained in this [`Error`].
-    pub(super) fn new(kind: ErrorKind, error: &'static str) -> Error {
+    pub(crate) fn new(kind: ErrorKind, error: &'static str) -> Error {
         Error { kind, error }
     }
 
     /// Returns the corresponding [`ErrorKind`] for this error.
-    pub(super) fn kind(&self) -> ErrorKind {
+    pub(crate) fn kind(&self) -> ErrorKind {
         self.kind
     }
 }
@@ -8,4 +8,5 @@ mod impls;
 mod traits;
 
 pub use error::Error;
+pub(crate) use error::ErrorKind;
 pub use traits::{Read, Write};
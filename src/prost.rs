@@ -0,0 +1,155 @@
+//! Multihash Protobuf messages
+//!
+//! The [`Multihash`] message carries the code and the digest as separate fields, the conversions
+//! from and into [`crate::Multihash`] apply the same size rules as parsing the wire form. For
+//! APIs that store the multihash in a plain `bytes` field, use [`decode_bytes_field`] and
+//! [`encode_bytes_field`].
+//!
+//! The message corresponds to this Protobuf definition:
+//!
+//! ```protobuf
+//! message Multihash {
+//!   uint64 code = 1;
+//!   bytes digest = 2;
+//! }
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::{Error, MultihashRef};
+
+/// A multihash as Protobuf message.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct Multihash {
+    /// The code of the multihash.
+    #[prost(uint64, tag = "1")]
+    pub code: u64,
+    /// The digest of the multihash, its length is the size.
+    #[prost(bytes = "vec", tag = "2")]
+    pub digest: Vec<u8>,
+}
+
+impl<const S: usize> From<crate::Multihash<S>> for Multihash {
+    fn from(multihash: crate::Multihash<S>) -> Self {
+        Self::from(&multihash)
+    }
+}
+
+impl<const S: usize> From<&crate::Multihash<S>> for Multihash {
+    fn from(multihash: &crate::Multihash<S>) -> Self {
+        Self {
+            code: multihash.code(),
+            digest: multihash.digest().to_vec(),
+        }
+    }
+}
+
+impl From<MultihashRef<'_>> for Multihash {
+    fn from(multihash: MultihashRef<'_>) -> Self {
+        Self {
+            code: multihash.code(),
+            digest: multihash.digest().to_vec(),
+        }
+    }
+}
+
+/// Fails if the digest is larger than the allocated size or the maximum size of 255 bytes.
+impl<const S: usize> TryFrom<Multihash> for crate::Multihash<S> {
+    type Error = Error;
+
+    fn try_from(message: Multihash) -> Result<Self, Self::Error> {
+        Self::try_from(&message)
+    }
+}
+
+/// Fails if the digest is larger than the allocated size or the maximum size of 255 bytes.
+impl<const S: usize> TryFrom<&Multihash> for crate::Multihash<S> {
+    type Error = Error;
+
+    fn try_from(message: &Multihash) -> Result<Self, Self::Error> {
+        let max_size = S.min(u8::MAX as usize);
+        if message.digest.len() > max_size {
+            return Err(Error::digest_too_large(
+                message.digest.len() as u64,
+                max_size,
+            ));
+        }
+        Self::wrap(message.code, &message.digest)
+    }
+}
+
+/// Fails if the digest is larger than the maximum size of 255 bytes.
+impl<'a> TryFrom<&'a Multihash> for MultihashRef<'a> {
+    type Error = Error;
+
+    fn try_from(message: &'a Multihash) -> Result<Self, Self::Error> {
+        Self::wrap(message.code, &message.digest)
+    }
+}
+
+/// Parses a raw Protobuf `bytes` field that contains the wire form of a multihash.
+///
+/// This is the same as [`crate::Multihash::from_bytes`], the whole field needs to be a single
+/// multihash.
+pub fn decode_bytes_field<const S: usize>(field: &[u8]) -> Result<crate::Multihash<S>, Error> {
+    crate::Multihash::from_bytes(field)
+}
+
+/// Encodes a multihash into its wire form for a raw Protobuf `bytes` field.
+pub fn encode_bytes_field<const S: usize>(multihash: &crate::Multihash<S>) -> Vec<u8> {
+    multihash.to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ErrorKind;
+    use ::prost::Message;
+    use alloc::vec;
+
+    #[test]
+    fn test_prost() {
+        let mh = crate::Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let message = Multihash::from(mh);
+        assert_eq!(message.code, 0x12);
+        assert_eq!(message.digest, b"hello world");
+
+        let encoded = message.encode_to_vec();
+        // Field 1 as varint, field 2 as length-delimited bytes.
+        assert_eq!(&encoded[..4], &[0x08, 0x12, 0x12, 0x0b]);
+        let decoded = Multihash::decode(&encoded[..]).unwrap();
+        assert_eq!(decoded, message);
+
+        assert_eq!(crate::Multihash::<32>::try_from(&decoded).unwrap(), mh);
+        assert_eq!(MultihashRef::try_from(&decoded).unwrap(), mh);
+        assert_eq!(Multihash::from(MultihashRef::from(&mh)), message);
+
+        let err = crate::Multihash::<8>::try_from(decoded).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::DigestTooLarge { size: 11, max: 8 });
+
+        let too_large = Multihash {
+            code: 0x12,
+            digest: vec![0; 256],
+        };
+        let err = crate::Multihash::<512>::try_from(&too_large).unwrap_err();
+        assert_eq!(
+            err.kind(),
+            ErrorKind::DigestTooLarge {
+                size: 256,
+                max: 255
+            }
+        );
+        assert!(MultihashRef::try_from(&too_large).is_err());
+    }
+
+    #[test]
+    fn test_bytes_field() {
+        let mh = crate::Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let field = encode_bytes_field(&mh);
+        assert_eq!(field, mh.to_bytes());
+        assert_eq!(decode_bytes_field::<32>(&field).unwrap(), mh);
+        assert!(decode_bytes_field::<8>(&field).is_err());
+        assert!(decode_bytes_field::<32>(&field[..5]).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! Multihash rkyv archiving
+
+use rkyv::bytecheck::{CheckBytes, Verify};
+use rkyv::munge::munge;
+use rkyv::rancor::{fail, Fallible, Source};
+use rkyv::{Archive, Deserialize, Place, Portable, Serialize};
+
+use crate::{Error, Multihash};
+
+/// An archived [`Multihash`].
+///
+/// It can be accessed directly in the archive without deserializing it. The layout is the code as
+/// little-endian `u64`, the size as `u8` and then the `S` bytes of the allocated digest, all
+/// fields are byte aligned and there is no padding.
+///
+/// When accessing untrusted archives with validation, archived multihashes with a size larger
+/// than `S` are rejected.
+#[derive(CheckBytes, Clone, Copy, Debug, Portable)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
+#[repr(C)]
+pub struct ArchivedMultihash<const S: usize> {
+    /// The code of the Multihash in little-endian byte order.
+    code: [u8; 8],
+    /// The actual size of the digest in bytes (not the allocated size).
+    size: u8,
+    /// The digest, the bytes after `size` are zero.
+    digest: [u8; S],
+}
+
+impl<const S: usize> ArchivedMultihash<S> {
+    /// Returns the code of the multihash.
+    pub const fn code(&self) -> u64 {
+        u64::from_le_bytes(self.code)
+    }
+
+    /// Returns the size of the digest.
+    pub const fn size(&self) -> u8 {
+        self.size
+    }
+
+    /// Returns the digest.
+    pub fn digest(&self) -> &[u8] {
+        // Unvalidated archives might contain a size that is too large.
+        &self.digest[..usize::from(self.size).min(S)]
+    }
+}
+
+impl<const S: usize> PartialEq for ArchivedMultihash<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.code() == other.code() && self.digest() == other.digest()
+    }
+}
+
+impl<const S: usize> Eq for ArchivedMultihash<S> {}
+
+impl<const S: usize> PartialEq<Multihash<S>> for ArchivedMultihash<S> {
+    fn eq(&self, other: &Multihash<S>) -> bool {
+        self.code() == other.code() && self.digest() == other.digest()
+    }
+}
+
+impl<const S: usize> PartialEq<ArchivedMultihash<S>> for Multihash<S> {
+    fn eq(&self, other: &ArchivedMultihash<S>) -> bool {
+        other == self
+    }
+}
+
+// Safety: `verify` only succeeds if the size fits into the allocated digest.
+#[allow(unsafe_code)]
+unsafe impl<C, const S: usize> Verify<C> for ArchivedMultihash<S>
+where
+    C: Fallible + ?Sized,
+    C::Error: Source,
+{
+    fn verify(&self, _context: &mut C) -> Result<(), C::Error> {
+        if usize::from(self.size) > S {
+            // The size follows the 8 bytes of the code.
+            fail!(Error::digest_too_large(self.size.into(), S).at(8));
+        }
+        Ok(())
+    }
+}
+
+impl<const S: usize> Archive for Multihash<S> {
+    type Archived = ArchivedMultihash<S>;
+    type Resolver = ();
+
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedMultihash { code, size, digest } = out);
+        code.write(self.code().to_le_bytes());
+        size.write(self.size());
+        // Don't archive the bytes after the digest, they might be left over from a truncation.
+        let mut bytes = [0; S];
+        bytes[..self.digest().len()].copy_from_slice(self.digest());
+        digest.write(bytes);
+    }
+}
+
+impl<Sr: Fallible + ?Sized, const S: usize> Serialize<Sr> for Multihash<S> {
+    fn serialize(&self, _: &mut Sr) -> Result<Self::Resolver, Sr::Error> {
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized, const S: usize> Deserialize<Multihash<S>, D> for ArchivedMultihash<S> {
+    fn deserialize(&self, _: &mut D) -> Result<Multihash<S>, D::Error> {
+        Ok(Multihash::wrap(self.code(), self.digest()).expect("the digest fits into S bytes"))
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    use rkyv::rancor;
+
+    #[test]
+    fn test_rkyv() {
+        let mh = Multihash::<32>::wrap(0x12, b"hello world")
+            .unwrap()
+            .truncate(5);
+        let bytes = rkyv::to_bytes::<rancor::Error>(&mh).unwrap();
+        assert_eq!(bytes.len(), 8 + 1 + 32);
+
+        let archived = rkyv::access::<ArchivedMultihash<32>, rancor::Error>(&bytes).unwrap();
+        assert_eq!(archived.code(), 0x12);
+        assert_eq!(archived.size(), 5);
+        assert_eq!(archived.digest(), b"hello");
+        assert_eq!(archived, &mh);
+        // The truncated bytes aren't archived.
+        assert_eq!(&bytes[9 + 5..], &[0; 27]);
+
+        let deserialized = rkyv::deserialize::<Multihash<32>, rancor::Error>(archived).unwrap();
+        assert_eq!(deserialized, mh);
+    }
+
+    #[test]
+    fn test_rkyv_size_too_large() {
+        use alloc::string::ToString;
+
+        let mh = Multihash::<32>::wrap(0x12, b"hello world").unwrap();
+        let mut bytes = rkyv::to_bytes::<rancor::Error>(&mh).unwrap();
+        bytes[8] = 33;
+        let err = rkyv::access::<ArchivedMultihash<32>, rancor::Error>(&bytes).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid multihash size 33 at byte offset 8."));
+    }
+}
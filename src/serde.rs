@@ -1,17 +1,29 @@
 //! Multihash Serde (de)serialization
+//!
+//! Multihashes are serialized as base58btc [multibase] string in human-readable formats and as
+//! their bytes in binary formats. Human-readable formats accept strings in any multibase, as well
+//! as the bytes that earlier versions produced. The `as_*` modules select a different
+//! representation for individual fields via `#[serde(with = "…")]`.
+//!
+//! [multibase]: https://github.com/multiformats/multibase
 
 use core::{fmt, mem, slice};
 
 use serde::{
-    de::{self, SeqAccess, Visitor},
+    de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor},
     ser, Deserialize, Deserializer, Serialize, Serializer,
 };
 
-use crate::Multihash;
+#[cfg(feature = "alloc")]
+use crate::MultihashBuf;
+use crate::{Multihash, MultihashRef};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
-/// The maximum serialization size of `code` is 9 bytes (a large varint encoded u64) and for `size`
-/// is 2 bytes  (a large varint encoded u8), this makes a total of 11 bytes.
-const MAXIMUM_PREFIX_SIZE: usize = 11;
+/// The maximum serialization size of `code` is 10 bytes (a large varint encoded u64) and for `size`
+/// is 2 bytes (a large varint encoded u8), this makes a total of 12 bytes. A varint byte holds 7
+/// bits of the number.
+const MAXIMUM_PREFIX_SIZE: usize = (u64::BITS.div_ceil(7) + u8::BITS.div_ceil(7)) as usize;
 
 /// The is currently no way to allocate an array that is some constant size bigger then a given
 /// const generic. Once `generic_const_exprs` are a thing, this struct will no longer be needed.
@@ -47,10 +59,14 @@ impl<const SIZE: usize> Serialize for Multihash<SIZE> {
     where
         S: Serializer,
     {
+        if serializer.is_human_readable() {
+            return serialize_as(self, serializer, Format::Multibase);
+        }
+
         let mut buffer = Buffer::<MAXIMUM_PREFIX_SIZE, SIZE>::new();
         let bytes_written = self
             .write(buffer.as_mut_slice())
-            .map_err(|_| ser::Error::custom("Failed to serialize Multihash"))?;
+            .map_err(ser::Error::custom)?;
         serializer.serialize_bytes(&buffer.as_slice()[..bytes_written])
     }
 }
@@ -68,8 +84,7 @@ impl<'de, const SIZE: usize> Visitor<'de> for BytesVisitor<SIZE> {
     where
         E: de::Error,
     {
-        Multihash::<SIZE>::from_bytes(bytes)
-            .map_err(|_| de::Error::custom("Failed to deserialize Multihash"))
+        Multihash::<SIZE>::from_bytes(bytes).map_err(de::Error::custom)
     }
 
     // Some Serde data formats interpret a byte stream as a sequence of bytes (e.g. `serde_json`).
@@ -86,12 +101,11 @@ impl<'de, const SIZE: usize> Visitor<'de> for BytesVisitor<SIZE> {
             bytes[pos] = byte;
             pos += 1;
             if pos >= bytes.len() {
-                return Err(de::Error::custom("Failed to deserialize Multihash"));
+                return Err(de::Error::invalid_length(pos, &self));
             }
         }
 
-        Multihash::<SIZE>::from_bytes(&bytes[..pos])
-            .map_err(|_| de::Error::custom("Failed to deserialize Multihash"))
+        Multihash::<SIZE>::from_bytes(&bytes[..pos]).map_err(de::Error::custom)
     }
 }
 
@@ -100,17 +114,591 @@ impl<'de, const SIZE: usize> Deserialize<'de> for Multihash<SIZE> {
     where
         D: Deserializer<'de>,
     {
+        if deserializer.is_human_readable() {
+            return deserialize_as(deserializer, Format::Multibase);
+        }
+
         deserializer.deserialize_bytes(BytesVisitor)
     }
 }
 
+impl Serialize for MultihashRef<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut buffer = [0; MAXIMUM_PREFIX_SIZE + u8::MAX as usize];
+        let bytes_written = self.write(&mut buffer[..]).map_err(ser::Error::custom)?;
+        let bytes = &buffer[..bytes_written];
+        if serializer.is_human_readable() {
+            return serializer.collect_str(&Encoded(bytes, Format::Multibase));
+        }
+
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+struct BorrowedBytesVisitor;
+
+impl<'de> Visitor<'de> for BorrowedBytesVisitor {
+    type Value = MultihashRef<'de>;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "a valid Multihash in borrowed bytes")
+    }
+
+    fn visit_borrowed_bytes<E>(self, bytes: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        MultihashRef::from_bytes(bytes).map_err(de::Error::custom)
+    }
+}
+
+/// Deserializes a view that borrows the digest from the input.
+///
+/// This only works with formats that can hand out borrowed bytes, which are mostly binary formats.
+/// Human-readable formats, or inputs that need to be copied (e.g. read from a stream), result in
+/// an error. Deserialize a [`Multihash`] in that case.
+impl<'de> Deserialize<'de> for MultihashRef<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // Human-readable formats usually don't support bytes, make sure the error is meaningful.
+        if deserializer.is_human_readable() {
+            return deserializer.deserialize_any(BorrowedBytesVisitor);
+        }
+
+        deserializer.deserialize_bytes(BorrowedBytesVisitor)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Serialize for MultihashBuf {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let bytes = self.to_bytes();
+        if serializer.is_human_readable() {
+            return serializer.collect_str(&Encoded(&bytes, Format::Multibase));
+        }
+
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+#[cfg(feature = "alloc")]
+struct BufBytesVisitor;
+
+#[cfg(feature = "alloc")]
+impl<'de> Visitor<'de> for BufBytesVisitor {
+    type Value = MultihashBuf;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "a valid Multihash in bytes")
+    }
+
+    // Only reached for human-readable formats.
+    fn visit_str<E>(self, string: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let (_base, bytes) = multibase::decode(string)
+            .map_err(|err| E::custom(crate::error::multibase_to_multihash_error(err)))?;
+        MultihashBuf::from_bytes(&bytes).map_err(de::Error::custom)
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        MultihashBuf::from_bytes(bytes).map_err(de::Error::custom)
+    }
+
+    // Some Serde data formats interpret a byte stream as a sequence of bytes (e.g. `serde_json`).
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let max_len = MAXIMUM_PREFIX_SIZE + MultihashBuf::DEFAULT_MAX_SIZE;
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(max_len));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+            if bytes.len() > max_len {
+                return Err(de::Error::invalid_length(bytes.len(), &self));
+            }
+        }
+
+        MultihashBuf::from_bytes(&bytes).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'de> Deserialize<'de> for MultihashBuf {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            return deserializer.deserialize_any(BufBytesVisitor);
+        }
+
+        deserializer.deserialize_bytes(BufBytesVisitor)
+    }
+}
+
+/// The string representations of human-readable formats and the `as_*` modules.
+#[derive(Clone, Copy)]
+enum Format {
+    /// Lowercase hex of the bytes, uppercase is accepted as well.
+    Hex,
+    /// Unpadded base64url of the bytes, without a multibase prefix.
+    Base64Url,
+    /// Unpadded lowercase base32 of the bytes, without a multibase prefix.
+    Base32,
+    /// A base58btc multibase string, any multibase and the bytes are accepted as well.
+    Multibase,
+    /// A struct with the code and the digest as fields, the digest is hex in human-readable
+    /// formats and bytes otherwise.
+    Structured,
+}
+
+impl Format {
+    const NAME: &'static str = "Multihash";
+    const FIELDS: &'static [&'static str] = &["code", "digest"];
+
+    fn expecting(self) -> &'static str {
+        match self {
+            Self::Hex => "a hex encoded Multihash",
+            Self::Base64Url => "an unpadded base64url encoded Multihash",
+            Self::Base32 => "an unpadded lowercase base32 encoded Multihash",
+            Self::Multibase => "a multibase encoded Multihash",
+            Self::Structured => "a Multihash with `code` and `digest` fields",
+        }
+    }
+
+    fn encode(self, bytes: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Hex | Self::Structured => {
+                bytes.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+            }
+            Self::Base64Url => f.write_str(&multibase::Base::Base64Url.encode(bytes)),
+            Self::Base32 => f.write_str(&multibase::Base::Base32Lower.encode(bytes)),
+            Self::Multibase => f.write_str(&multibase::encode(multibase::Base::Base58Btc, bytes)),
+        }
+    }
+
+    /// Decodes a string into the given slice, returns the number of decoded bytes.
+    fn decode_into<E: de::Error>(self, string: &str, out: &mut [u8]) -> Result<usize, E> {
+        match self {
+            Self::Hex | Self::Structured => self.decode_hex_into(string, out),
+            Self::Base64Url => self.copy_into(multibase::Base::Base64Url.decode(string), out),
+            Self::Base32 => self.copy_into(multibase::Base::Base32Lower.decode(string), out),
+            Self::Multibase => {
+                let decoded = multibase::decode(string).map(|(_base, bytes)| bytes);
+                self.copy_into(decoded, out)
+            }
+        }
+    }
+
+    fn decode_hex_into<E: de::Error>(self, string: &str, out: &mut [u8]) -> Result<usize, E> {
+        let invalid = || E::invalid_value(de::Unexpected::Str(string), &self.expecting());
+        // `u8::from_str_radix` accepts a sign, so check the digits upfront.
+        if string.len() % 2 != 0 || !string.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let len = string.len() / 2;
+        if len > out.len() {
+            return Err(E::invalid_length(len, &self.expecting()));
+        }
+        for (byte, pair) in out.iter_mut().zip(string.as_bytes().chunks(2)) {
+            let pair = core::str::from_utf8(pair).map_err(|_| invalid())?;
+            *byte = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+        }
+        Ok(len)
+    }
+
+    fn copy_into<E: de::Error>(
+        self,
+        decoded: Result<Vec<u8>, multibase::Error>,
+        out: &mut [u8],
+    ) -> Result<usize, E> {
+        let bytes =
+            decoded.map_err(|err| E::custom(crate::error::multibase_to_multihash_error(err)))?;
+        out.get_mut(..bytes.len())
+            .ok_or_else(|| E::invalid_length(bytes.len(), &self.expecting()))?
+            .copy_from_slice(&bytes);
+        Ok(bytes.len())
+    }
+}
+
+/// Displays bytes in the given format.
+struct Encoded<'a>(&'a [u8], Format);
+
+impl fmt::Display for Encoded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.1.encode(self.0, f)
+    }
+}
+
+/// The digest of the structured format.
+struct Digest<'a>(&'a [u8]);
+
+impl Serialize for Digest<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(&Encoded(self.0, Format::Structured))
+        } else {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+}
+
+fn serialize_as<S: Serializer, const SIZE: usize>(
+    multihash: &Multihash<SIZE>,
+    serializer: S,
+    format: Format,
+) -> Result<S::Ok, S::Error> {
+    if let Format::Structured = format {
+        use ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct(Format::NAME, 2)?;
+        state.serialize_field("code", &multihash.code())?;
+        state.serialize_field("digest", &Digest(multihash.digest()))?;
+        return state.end();
+    }
+
+    let mut buffer = Buffer::<MAXIMUM_PREFIX_SIZE, SIZE>::new();
+    let bytes_written = multihash
+        .write(buffer.as_mut_slice())
+        .map_err(ser::Error::custom)?;
+    serializer.collect_str(&Encoded(&buffer.as_slice()[..bytes_written], format))
+}
+
+fn deserialize_as<'de, D: Deserializer<'de>, const SIZE: usize>(
+    deserializer: D,
+    format: Format,
+) -> Result<Multihash<SIZE>, D::Error> {
+    match format {
+        Format::Structured => {
+            deserializer.deserialize_struct(Format::NAME, Format::FIELDS, FormatVisitor(format))
+        }
+        Format::Multibase => deserializer.deserialize_any(FormatVisitor(format)),
+        _ => deserializer.deserialize_str(FormatVisitor(format)),
+    }
+}
+
+struct FormatVisitor<const SIZE: usize>(Format);
+
+impl<'de, const SIZE: usize> Visitor<'de> for FormatVisitor<SIZE> {
+    type Value = Multihash<SIZE>;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(self.0.expecting())
+    }
+
+    fn visit_str<E>(self, string: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let mut buffer = Buffer::<MAXIMUM_PREFIX_SIZE, SIZE>::new();
+        let len = self.0.decode_into(string, buffer.as_mut_slice())?;
+        Multihash::from_bytes(&buffer.as_slice()[..len]).map_err(de::Error::custom)
+    }
+
+    // Only reached for the multibase format, which accepts the default representation as well.
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        BytesVisitor.visit_bytes(bytes)
+    }
+
+    // The sequence is either the default representation of the multibase format, or a struct of
+    // the structured format.
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        if !matches!(self.0, Format::Structured) {
+            return BytesVisitor.visit_seq(seq);
+        }
+
+        let code = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let (digest, len) = seq
+            .next_element_seed(DigestSeed::<SIZE>)?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Multihash::wrap(code, &digest[..len]).map_err(de::Error::custom)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut code = None;
+        let mut digest = None;
+        while let Some(field) = map.next_key_seed(FieldSeed)? {
+            match field {
+                "code" if code.is_some() => return Err(de::Error::duplicate_field("code")),
+                "code" => code = Some(map.next_value()?),
+                _ if digest.is_some() => return Err(de::Error::duplicate_field("digest")),
+                _ => digest = Some(map.next_value_seed(DigestSeed::<SIZE>)?),
+            }
+        }
+        let code = code.ok_or_else(|| de::Error::missing_field("code"))?;
+        let (digest, len) = digest.ok_or_else(|| de::Error::missing_field("digest"))?;
+        Multihash::wrap(code, &digest[..len]).map_err(de::Error::custom)
+    }
+}
+
+/// Deserializes a field name of the structured format.
+struct FieldSeed;
+
+impl<'de> DeserializeSeed<'de> for FieldSeed {
+    type Value = &'static str;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl Visitor<'_> for FieldSeed {
+    type Value = &'static str;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("`code` or `digest`")
+    }
+
+    fn visit_str<E>(self, name: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Format::FIELDS
+            .iter()
+            .find(|field| **field == name)
+            .copied()
+            .ok_or_else(|| de::Error::unknown_field(name, Format::FIELDS))
+    }
+}
+
+/// Deserializes the digest of the structured format into an array and its length.
+struct DigestSeed<const SIZE: usize>;
+
+impl<'de, const SIZE: usize> DeserializeSeed<'de> for DigestSeed<SIZE> {
+    type Value = ([u8; SIZE], usize);
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(self)
+        } else {
+            deserializer.deserialize_bytes(self)
+        }
+    }
+}
+
+impl<const SIZE: usize> Visitor<'_> for DigestSeed<SIZE> {
+    type Value = ([u8; SIZE], usize);
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "a digest of at most {SIZE} bytes")
+    }
+
+    fn visit_str<E>(self, string: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let mut digest = [0; SIZE];
+        let len = Format::Structured.decode_into(string, &mut digest)?;
+        Ok((digest, len))
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let mut digest = [0; SIZE];
+        digest
+            .get_mut(..bytes.len())
+            .ok_or_else(|| de::Error::invalid_length(bytes.len(), &self))?
+            .copy_from_slice(bytes);
+        Ok((digest, bytes.len()))
+    }
+}
+
+macro_rules! with_module {
+    ($(#[$attr:meta])* $module:ident, $format:expr) => {
+        $(#[$attr])*
+        pub mod $module {
+            use super::*;
+
+            /// Serializes a multihash, for use with `#[serde(serialize_with = "…")]`.
+            pub fn serialize<S, const SIZE: usize>(
+                multihash: &Multihash<SIZE>,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serialize_as(multihash, serializer, $format)
+            }
+
+            /// Deserializes a multihash, for use with `#[serde(deserialize_with = "…")]`.
+            pub fn deserialize<'de, D, const SIZE: usize>(
+                deserializer: D,
+            ) -> Result<Multihash<SIZE>, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                deserialize_as(deserializer, $format)
+            }
+
+            struct SerializeAs<'a, const SIZE: usize>(&'a Multihash<SIZE>);
+
+            impl<const SIZE: usize> Serialize for SerializeAs<'_, SIZE> {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serialize(self.0, serializer)
+                }
+            }
+
+            struct DeserializeAs<const SIZE: usize>(Multihash<SIZE>);
+
+            impl<'de, const SIZE: usize> Deserialize<'de> for DeserializeAs<SIZE> {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    deserialize(deserializer).map(Self)
+                }
+            }
+
+            /// The same representation for an optional multihash.
+            pub mod option {
+                use super::*;
+
+                /// Serializes an optional multihash.
+                pub fn serialize<S, const SIZE: usize>(
+                    multihash: &Option<Multihash<SIZE>>,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    multihash.as_ref().map(SerializeAs).serialize(serializer)
+                }
+
+                /// Deserializes an optional multihash.
+                pub fn deserialize<'de, D, const SIZE: usize>(
+                    deserializer: D,
+                ) -> Result<Option<Multihash<SIZE>>, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    let multihash = Option::<DeserializeAs<SIZE>>::deserialize(deserializer)?;
+                    Ok(multihash.map(|multihash| multihash.0))
+                }
+            }
+
+            /// The same representation for a list of multihashes.
+            #[cfg(feature = "alloc")]
+            pub mod vec {
+                use super::*;
+
+                /// Serializes a list of multihashes.
+                pub fn serialize<S, const SIZE: usize>(
+                    multihashes: &[Multihash<SIZE>],
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    serializer.collect_seq(multihashes.iter().map(SerializeAs))
+                }
+
+                /// Deserializes a list of multihashes.
+                pub fn deserialize<'de, D, const SIZE: usize>(
+                    deserializer: D,
+                ) -> Result<Vec<Multihash<SIZE>>, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    let multihashes = Vec::<DeserializeAs<SIZE>>::deserialize(deserializer)?;
+                    Ok(multihashes.into_iter().map(|multihash| multihash.0).collect())
+                }
+            }
+        }
+    };
+}
+
+with_module!(
+    /// (De)serializes a multihash as lowercase hex string of its bytes.
+    ///
+    /// Use it with `#[serde(with = "multihash::serde::as_hex")]`, or `as_hex::option` and
+    /// `as_hex::vec` for `Option` and `Vec` fields. Uppercase hex is accepted when deserializing.
+    as_hex,
+    Format::Hex
+);
+
+with_module!(
+    /// (De)serializes a multihash as unpadded base64url string of its bytes.
+    ///
+    /// Use it with `#[serde(with = "multihash::serde::as_base64url")]`, or `as_base64url::option`
+    /// and `as_base64url::vec` for `Option` and `Vec` fields. The string has no multibase prefix.
+    as_base64url,
+    Format::Base64Url
+);
+
+with_module!(
+    /// (De)serializes a multihash as unpadded lowercase base32 string of its bytes.
+    ///
+    /// Use it with `#[serde(with = "multihash::serde::as_base32")]`, or `as_base32::option` and
+    /// `as_base32::vec` for `Option` and `Vec` fields. The string has no multibase prefix.
+    as_base32,
+    Format::Base32
+);
+
+with_module!(
+    /// (De)serializes a multihash as a struct with a `code` and a `digest` field.
+    ///
+    /// Use it with `#[serde(with = "multihash::serde::as_structured")]`, or
+    /// `as_structured::option` and `as_structured::vec` for `Option` and `Vec` fields. The digest
+    /// is a hex string in human-readable formats and bytes in binary formats.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use multihash::Multihash;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Deserialize, Serialize)]
+    /// struct Record {
+    ///     #[serde(with = "multihash::serde::as_structured")]
+    ///     hash: Multihash<32>,
+    ///     #[serde(with = "multihash::serde::as_hex::option")]
+    ///     previous: Option<Multihash<32>>,
+    /// }
+    ///
+    /// let record = Record {
+    ///     hash: Multihash::wrap(0x12, &[0xab, 0xcd]).unwrap(),
+    ///     previous: None,
+    /// };
+    /// assert_eq!(
+    ///     serde_json::to_string(&record).unwrap(),
+    ///     r#"{"hash":{"code":18,"digest":"abcd"},"previous":null}"#
+    /// );
+    /// ```
+    as_structured,
+    Format::Structured
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use std::ptr;
+    use alloc::{format, string::ToString, vec};
+    use core::ptr;
 
-    use serde_test::{assert_tokens, Token};
+    use serde_test::{assert_tokens, Configure, Token};
 
     const SHA2_256_CODE: u64 = 0x12;
     const DIGEST: [u8; 32] = [
@@ -120,8 +708,8 @@ mod tests {
 
     #[test]
     fn test_serde_json() {
-        // This is a concatenation of `SHA2_256_CODE + DIGEST_LENGTH + DIGEST`.
-        let expected_json = format!("[{},{},159,228,204,198,222,22,114,79,58,48,199,232,242,84,243,198,71,25,134,172,177,248,216,207,142,150,206,42,215,219,231,251]", SHA2_256_CODE as u8, DIGEST.len() as u8);
+        // This is the base58btc multibase string of `SHA2_256_CODE + DIGEST_LENGTH + DIGEST`.
+        let expected_json = r#""zQmZ6otJLj8utnm45nUnLax88C4rCPD3f9vANE4kzicZVoG""#;
 
         let mh = Multihash::<32>::wrap(SHA2_256_CODE, &DIGEST).unwrap();
 
@@ -132,6 +720,24 @@ mod tests {
         assert_eq!(mh, mh_decoded);
     }
 
+    #[test]
+    fn test_serde_json_compat() {
+        let mh = Multihash::<32>::wrap(SHA2_256_CODE, &DIGEST).unwrap();
+
+        // Earlier versions serialized the bytes, which `serde_json` renders as an array.
+        let array_json = format!("[{},{},159,228,204,198,222,22,114,79,58,48,199,232,242,84,243,198,71,25,134,172,177,248,216,207,142,150,206,42,215,219,231,251]", SHA2_256_CODE as u8, DIGEST.len() as u8);
+        let mh_decoded: Multihash<32> = serde_json::from_str(&array_json).unwrap();
+        assert_eq!(mh, mh_decoded);
+
+        // Strings in any multibase are accepted.
+        let base32 = mh.to_string_base(multibase::Base::Base32Lower);
+        let mh_decoded: Multihash<32> = serde_json::from_str(&format!(r#""{base32}""#)).unwrap();
+        assert_eq!(mh, mh_decoded);
+
+        let err = serde_json::from_str::<Multihash<32>>(r#""not a multihash""#).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid multibase string"));
+    }
+
     #[test]
     fn test_serde_test() {
         // This is a concatenation of `SHA2_256_CODE + DIGEST_LENGTH + DIGEST`.
@@ -175,52 +781,245 @@ mod tests {
         let mh = Multihash::<32>::wrap(SHA2_256_CODE, &DIGEST).unwrap();
 
         // As bytes.
-        assert_tokens(&mh, &[Token::Bytes(&ENCODED_MULTIHASH_BYTES)]);
+        assert_tokens(&mh.compact(), &[Token::Bytes(&ENCODED_MULTIHASH_BYTES)]);
+
+        // As multibase string in human-readable formats.
+        assert_tokens(
+            &mh.readable(),
+            &[Token::Str(
+                "zQmZ6otJLj8utnm45nUnLax88C4rCPD3f9vANE4kzicZVoG",
+            )],
+        );
+
+        // As sequence, in both compact and human-readable formats.
+        let seq_tokens = [
+            Token::Seq { len: Some(34) },
+            Token::U8(SHA2_256_CODE as u8),
+            Token::U8(DIGEST.len() as u8),
+            Token::U8(159),
+            Token::U8(228),
+            Token::U8(204),
+            Token::U8(198),
+            Token::U8(222),
+            Token::U8(22),
+            Token::U8(114),
+            Token::U8(79),
+            Token::U8(58),
+            Token::U8(48),
+            Token::U8(199),
+            Token::U8(232),
+            Token::U8(242),
+            Token::U8(84),
+            Token::U8(243),
+            Token::U8(198),
+            Token::U8(71),
+            Token::U8(25),
+            Token::U8(134),
+            Token::U8(172),
+            Token::U8(177),
+            Token::U8(248),
+            Token::U8(216),
+            Token::U8(207),
+            Token::U8(142),
+            Token::U8(150),
+            Token::U8(206),
+            Token::U8(42),
+            Token::U8(215),
+            Token::U8(219),
+            Token::U8(231),
+            Token::U8(251),
+            Token::SeqEnd,
+        ];
+        serde_test::assert_de_tokens(&mh.compact(), &seq_tokens);
+        serde_test::assert_de_tokens(&mh.readable(), &seq_tokens);
+    }
+
+    #[test]
+    fn test_serde_error() {
+        let err = serde_json::from_str::<Multihash<32>>("[18,32,1]").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Unexpected end of input at byte offset 2."));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_serde_multihash_buf() {
+        let mh = MultihashBuf::wrap(0x00, [7; 300]);
+        let bytes = mh.to_bytes();
+
+        let de = de::value::BytesDeserializer::<de::value::Error>::new(&bytes);
+        assert_eq!(MultihashBuf::deserialize(de).unwrap(), mh);
+
+        let json = serde_json::to_string(&mh).unwrap();
+        let base58btc = multibase::encode(multibase::Base::Base58Btc, &bytes);
+        assert_eq!(json, format!(r#""{base58btc}""#));
+        let mh_decoded: MultihashBuf = serde_json::from_str(&json).unwrap();
+        assert_eq!(mh, mh_decoded);
+        let mh_decoded: MultihashBuf =
+            serde_json::from_str(&serde_json::to_string(&bytes).unwrap()).unwrap();
+        assert_eq!(mh, mh_decoded);
+
+        // Same representation as a fixed size multihash if the digest fits.
+        let mh = Multihash::<32>::wrap(SHA2_256_CODE, &DIGEST).unwrap();
+        assert_eq!(
+            serde_json::to_string(&MultihashBuf::from(mh)).unwrap(),
+            serde_json::to_string(&mh).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_serde_multihash_ref() {
+        const BYTES: [u8; 5] = [0x12, 0x03, 0x01, 0x02, 0x03];
+
+        let mh_ref = MultihashRef::from_bytes(&BYTES).unwrap();
+        assert_tokens(&mh_ref.compact(), &[Token::BorrowedBytes(&BYTES)]);
+
+        // The digest points into the input.
+        let de = de::value::BorrowedBytesDeserializer::<de::value::Error>::new(&BYTES);
+        let decoded = MultihashRef::deserialize(de).unwrap();
+        assert!(ptr::eq(decoded.digest(), &BYTES[2..]));
+
+        // Bytes that can't be borrowed aren't accepted.
+        serde_test::assert_de_tokens_error::<serde_test::Compact<MultihashRef>>(
+            &[Token::Bytes(&BYTES)],
+            "invalid type: byte array, expected a valid Multihash in borrowed bytes",
+        );
+
+        // Same representation as a fixed size multihash.
+        let mh = Multihash::<32>::wrap(SHA2_256_CODE, &DIGEST).unwrap();
+        assert_eq!(
+            serde_json::to_string(&MultihashRef::from(&mh)).unwrap(),
+            serde_json::to_string(&mh).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_serde_largest_multihash() {
+        let digest = [7; u8::MAX as usize];
+        let mh = Multihash::<{ u8::MAX as usize }>::wrap(u64::MAX, &digest).unwrap();
+        // The tokens need static bytes.
+        let bytes = mh.to_bytes().leak();
+        assert_eq!(bytes.len(), MAXIMUM_PREFIX_SIZE + digest.len());
+
+        let mh_ref = MultihashRef::from(&mh);
+        serde_test::assert_ser_tokens(&mh_ref.compact(), &[Token::Bytes(bytes)]);
+        assert_tokens(&mh.compact(), &[Token::Bytes(bytes)]);
+
+        let json = serde_json::to_string(&mh_ref).unwrap();
+        assert_eq!(json, serde_json::to_string(&mh).unwrap());
+        assert_eq!(
+            serde_json::from_str::<Multihash<{ u8::MAX as usize }>>(&json).unwrap(),
+            mh
+        );
+    }
+
+    #[test]
+    fn test_as_modules() {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, Deserialize, PartialEq, Serialize)]
+        struct Record {
+            #[serde(with = "as_hex")]
+            hex: Multihash<32>,
+            #[serde(with = "as_structured")]
+            structured: Multihash<32>,
+            #[serde(with = "as_hex::option")]
+            option: Option<Multihash<32>>,
+            #[serde(with = "as_structured::vec")]
+            vec: Vec<Multihash<32>>,
+        }
+
+        let mh = Multihash::<32>::wrap(SHA2_256_CODE, &DIGEST[..2]).unwrap();
+        let record = Record {
+            hex: mh,
+            structured: mh,
+            option: Some(mh),
+            vec: vec![mh, mh],
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(
+            json,
+            r#"{"hex":"12029fe4","structured":{"code":18,"digest":"9fe4"},"option":"12029fe4","vec":[{"code":18,"digest":"9fe4"},{"code":18,"digest":"9fe4"}]}"#
+        );
+        assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+
+        // Uppercase hex and the sequence form of a struct are accepted.
+        let json = r#"{"hex":"12029FE4","structured":[18,"9fe4"],"option":null,"vec":[]}"#;
+        let record = serde_json::from_str::<Record>(json).unwrap();
+        assert_eq!(record.hex, mh);
+        assert_eq!(record.structured, mh);
+        assert_eq!(record.option, None);
+
+        let err = serde_json::from_str::<Record>(r#"{"hex":"12029fe"}"#).unwrap_err();
+        assert!(err.to_string().contains("expected a hex encoded Multihash"));
+        let err = serde_json::from_str::<Record>(r#"{"hex":"12+0"}"#).unwrap_err();
+        assert!(err.to_string().contains("expected a hex encoded Multihash"));
+    }
+
+    #[test]
+    fn test_as_structured_compact() {
+        struct Compact(Multihash<32>);
+
+        impl Serialize for Compact {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                as_structured::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for Compact {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                as_structured::deserialize(deserializer).map(Self)
+            }
+        }
+
+        impl PartialEq for Compact {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
 
-        // As sequence.
-        serde_test::assert_de_tokens(
-            &mh,
+        impl fmt::Debug for Compact {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        let mh = Multihash::<32>::wrap(SHA2_256_CODE, &DIGEST).unwrap();
+        assert_tokens(
+            &Compact(mh).compact(),
             &[
-                Token::Seq { len: Some(34) },
-                Token::U8(SHA2_256_CODE as u8),
-                Token::U8(DIGEST.len() as u8),
-                Token::U8(159),
-                Token::U8(228),
-                Token::U8(204),
-                Token::U8(198),
-                Token::U8(222),
-                Token::U8(22),
-                Token::U8(114),
-                Token::U8(79),
-                Token::U8(58),
-                Token::U8(48),
-                Token::U8(199),
-                Token::U8(232),
-                Token::U8(242),
-                Token::U8(84),
-                Token::U8(243),
-                Token::U8(198),
-                Token::U8(71),
-                Token::U8(25),
-                Token::U8(134),
-                Token::U8(172),
-                Token::U8(177),
-                Token::U8(248),
-                Token::U8(216),
-                Token::U8(207),
-                Token::U8(142),
-                Token::U8(150),
-                Token::U8(206),
-                Token::U8(42),
-                Token::U8(215),
-                Token::U8(219),
-                Token::U8(231),
-                Token::U8(251),
-                Token::SeqEnd,
+                Token::Struct {
+                    name: "Multihash",
+                    len: 2,
+                },
+                Token::Str("code"),
+                Token::U64(SHA2_256_CODE),
+                Token::Str("digest"),
+                Token::Bytes(&DIGEST),
+                Token::StructEnd,
             ],
         );
     }
 
+    #[test]
+    fn test_as_multibase_encodings() {
+        let mh = Multihash::<32>::wrap(SHA2_256_CODE, &DIGEST[..2]).unwrap();
+        let mut json = Vec::new();
+        as_base64url::serialize(&mh, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        assert_eq!(json, br#""EgKf5A""#);
+        let decoded: Multihash<32> =
+            as_base64url::deserialize(&mut serde_json::Deserializer::from_slice(&json)).unwrap();
+        assert_eq!(decoded, mh);
+
+        let mut json = Vec::new();
+        as_base32::vec::serialize(&[mh], &mut serde_json::Serializer::new(&mut json)).unwrap();
+        assert_eq!(json, br#"["cibj7za"]"#);
+        let decoded: Vec<Multihash<32>> =
+            as_base32::vec::deserialize(&mut serde_json::Deserializer::from_slice(&json)).unwrap();
+        assert_eq!(decoded, [mh]);
+    }
+
     #[test]
     fn test_buffer_alignment() {
         const SIZE_FIRST: usize = 11;